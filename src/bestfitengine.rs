@@ -0,0 +1,633 @@
+//! Deencoding engine adding Windows "best fit" to another engine
+//!
+//! When `WideCharToMultiByte` converts to a code page such as 1252 without the
+//! `WC_NO_BEST_FIT_CHARS` flag, characters that the code page cannot represent
+//! are not rejected, but silently replaced with a similar looking character:
+//! `'Ł'` becomes `'L'`, `'∞'` becomes `'8'`, and fullwidth `'Ａ'` becomes `'A'`.
+//! Whatever is left is replaced with the default character `'?'`.
+//!
+//! Each code page has its own replacements, which Microsoft publishes in the
+//! `WCTABLE` section of `bestfit1252.txt` and its siblings: `'ǘ'` becomes `'ü'`
+//! in Codepage 1252, and `'À'` becomes `'A'` in Codepage 1250, which has no
+//! `'À'`. This module has the tables of Codepages 1250 and 1252 to 1255 for
+//! Latin letters, symbols and fullwidth forms, as the character each one
+//! becomes rather than its byte.
+//!
+//! This engine wraps another engine, with the table of its code page: encoding
+//! passes each character to the inner engine, and falls back to its entry in
+//! the table, then to `'?'`, when that fails. Decoding is left to the inner
+//! engine, since best fit only applies in that direction.
+
+use crate::engine::Engine;
+
+/// The best fit table of Codepage 1250, from `bestfit1250.txt`.
+pub const CP1250: &[(char, char)] = &[
+    ('¢', 'c'), ('¥', 'Y'), ('ª', 'a'), ('²', '2'), ('³', '3'), ('¹', '1'),
+    ('º', 'o'), ('À', 'A'), ('Ã', 'A'), ('Å', 'A'), ('Æ', 'A'), ('È', 'E'),
+    ('Ê', 'E'), ('Ì', 'I'), ('Ï', 'I'), ('Ð', 'D'), ('Ñ', 'N'), ('Ò', 'O'),
+    ('Õ', 'O'), ('Ø', 'O'), ('Ù', 'U'), ('Û', 'U'), ('Þ', 'T'), ('à', 'a'),
+    ('ã', 'a'), ('å', 'a'), ('æ', 'a'), ('è', 'e'), ('ê', 'e'), ('ì', 'i'),
+    ('ï', 'i'), ('ð', 'd'), ('ñ', 'n'), ('ò', 'o'), ('õ', 'o'), ('ø', 'o'),
+    ('ù', 'u'), ('û', 'u'), ('þ', 't'), ('ÿ', 'y'), ('Ā', 'A'), ('ā', 'a'),
+    ('Ĉ', 'C'), ('ĉ', 'c'), ('Ċ', 'C'), ('ċ', 'c'), ('Ē', 'E'), ('ē', 'e'),
+    ('Ĕ', 'E'), ('ĕ', 'e'), ('Ė', 'E'), ('ė', 'e'), ('Ĝ', 'G'), ('ĝ', 'g'),
+    ('Ğ', 'G'), ('ğ', 'g'), ('Ġ', 'G'), ('ġ', 'g'), ('Ģ', 'G'), ('ģ', 'g'),
+    ('Ĥ', 'H'), ('ĥ', 'h'), ('Ħ', 'H'), ('ħ', 'h'), ('Ĩ', 'I'), ('ĩ', 'i'),
+    ('Ī', 'I'), ('ī', 'i'), ('Ĭ', 'I'), ('ĭ', 'i'), ('Į', 'I'), ('į', 'i'),
+    ('İ', 'I'), ('ı', 'i'), ('Ĵ', 'J'), ('ĵ', 'j'), ('Ķ', 'K'), ('ķ', 'k'),
+    ('Ļ', 'L'), ('ļ', 'l'), ('Ņ', 'N'), ('ņ', 'n'), ('Ō', 'O'), ('ō', 'o'),
+    ('Ŏ', 'O'), ('ŏ', 'o'), ('Ŗ', 'R'), ('ŗ', 'r'), ('Ŝ', 'S'), ('ŝ', 's'),
+    ('Ŧ', 'T'), ('ŧ', 't'), ('Ũ', 'U'), ('ũ', 'u'), ('Ū', 'U'), ('ū', 'u'),
+    ('Ŭ', 'U'), ('ŭ', 'u'), ('Ų', 'U'), ('ų', 'u'), ('Ŵ', 'W'), ('ŵ', 'w'),
+    ('Ŷ', 'Y'), ('ŷ', 'y'), ('Ÿ', 'Y'), ('ƀ', 'b'), ('Ɖ', 'D'), ('Ƒ', 'f'),
+    ('Ɨ', 'I'), ('ƚ', 'l'), ('Ɵ', 'O'), ('Ơ', 'O'), ('ơ', 'o'), ('ƫ', 't'),
+    ('Ʈ', 'T'), ('Ư', 'U'), ('ư', 'u'), ('ƶ', 'z'), ('ǀ', '|'), ('ǃ', '!'),
+    ('Ǎ', 'A'), ('ǎ', 'a'), ('Ǐ', 'I'), ('ǐ', 'i'), ('Ǒ', 'O'), ('ǒ', 'o'),
+    ('Ǔ', 'U'), ('ǔ', 'u'), ('Ǖ', 'Ü'), ('ǖ', 'ü'), ('Ǘ', 'Ü'), ('ǘ', 'ü'),
+    ('Ǚ', 'Ü'), ('ǚ', 'ü'), ('Ǜ', 'Ü'), ('ǜ', 'ü'), ('Ǟ', 'Ä'), ('ǟ', 'ä'),
+    ('Ǡ', 'A'), ('ǡ', 'a'), ('Ǥ', 'G'), ('ǥ', 'g'), ('Ǧ', 'G'), ('ǧ', 'g'),
+    ('Ǩ', 'K'), ('ǩ', 'k'), ('Ǫ', 'O'), ('ǫ', 'o'), ('Ǭ', 'O'), ('ǭ', 'o'),
+    ('ǰ', 'j'), ('Ǵ', 'G'), ('ǵ', 'g'), ('Ǹ', 'N'), ('ǹ', 'n'), ('Ǻ', 'A'),
+    ('ǻ', 'a'), ('Ȁ', 'A'), ('ȁ', 'a'), ('Ȃ', 'A'), ('ȃ', 'a'), ('Ȅ', 'E'),
+    ('ȅ', 'e'), ('Ȇ', 'E'), ('ȇ', 'e'), ('Ȉ', 'I'), ('ȉ', 'i'), ('Ȋ', 'I'),
+    ('ȋ', 'i'), ('Ȍ', 'O'), ('ȍ', 'o'), ('Ȏ', 'O'), ('ȏ', 'o'), ('Ȑ', 'R'),
+    ('ȑ', 'r'), ('Ȓ', 'R'), ('ȓ', 'r'), ('Ȕ', 'U'), ('ȕ', 'u'), ('Ȗ', 'U'),
+    ('ȗ', 'u'), ('Ș', 'S'), ('ș', 's'), ('Ț', 'T'), ('ț', 't'), ('Ȟ', 'H'),
+    ('ȟ', 'h'), ('Ȧ', 'A'), ('ȧ', 'a'), ('Ȩ', 'E'), ('ȩ', 'e'), ('Ȫ', 'Ö'),
+    ('ȫ', 'ö'), ('Ȭ', 'O'), ('ȭ', 'o'), ('Ȯ', 'O'), ('ȯ', 'o'), ('Ȱ', 'O'),
+    ('ȱ', 'o'), ('Ȳ', 'Y'), ('ȳ', 'y'), ('ɡ', 'g'), ('ʹ', '\''), ('ʺ', '"'),
+    ('ʼ', '\''), ('˄', '^'), ('ˈ', '\''), ('ˉ', '-'), ('ˊ', '´'), ('ˋ', '`'),
+    ('ˍ', '_'), ('˚', '°'), ('\u{300}', '`'), ('\u{301}', '´'),
+    ('\u{302}', '^'), ('\u{303}', '~'), ('\u{304}', '-'), ('\u{305}', '-'),
+    ('\u{308}', '¨'), ('\u{30a}', '°'), ('\u{30e}', '"'), ('\u{327}', '¸'),
+    ('\u{331}', '_'), ('\u{332}', '_'), (';', ';'), ('Γ', 'G'), ('Θ', 'T'),
+    ('Σ', 'S'), ('Φ', 'F'), ('Ω', 'O'), ('α', 'a'), ('β', 'ß'), ('δ', 'd'),
+    ('ε', 'e'), ('μ', 'µ'), ('π', 'p'), ('σ', 's'), ('τ', 't'), ('φ', 'f'),
+    ('һ', 'h'), ('։', ':'), ('٪', '%'), ('\u{2000}', '\u{20}'),
+    ('\u{2001}', '\u{20}'), ('\u{2002}', '\u{20}'), ('\u{2003}', '\u{20}'),
+    ('\u{2004}', '\u{20}'), ('\u{2005}', '\u{20}'), ('\u{2006}', '\u{20}'),
+    ('‐', '-'), ('‑', '-'), ('‗', '='), ('′', '\''), ('‵', '`'), ('⁄', '/'),
+    ('⁴', '4'), ('⁵', '5'), ('⁶', '6'), ('⁷', '7'), ('⁸', '8'), ('ⁿ', 'n'),
+    ('₀', '0'), ('₁', '1'), ('₂', '2'), ('₃', '3'), ('₄', '4'), ('₅', '5'),
+    ('₆', '6'), ('₇', '7'), ('₈', '8'), ('₉', '9'), ('₧', 'P'), ('ℂ', 'C'),
+    ('ℇ', 'E'), ('ℊ', 'g'), ('ℋ', 'H'), ('ℌ', 'H'), ('ℍ', 'H'), ('ℎ', 'h'),
+    ('ℐ', 'I'), ('ℑ', 'I'), ('ℒ', 'L'), ('ℓ', 'l'), ('ℕ', 'N'), ('℘', 'P'),
+    ('ℙ', 'P'), ('ℚ', 'Q'), ('ℛ', 'R'), ('ℜ', 'R'), ('ℝ', 'R'), ('ℤ', 'Z'),
+    ('ℨ', 'Z'), ('K', 'K'), ('Å', 'A'), ('ℬ', 'B'), ('ℭ', 'C'), ('℮', 'e'),
+    ('ℯ', 'e'), ('ℰ', 'E'), ('ℱ', 'F'), ('ℳ', 'M'), ('ℴ', 'o'), ('−', '-'),
+    ('∓', '±'), ('∕', '/'), ('∖', '\\'), ('∗', '*'), ('∘', '°'), ('∙', '·'),
+    ('√', 'v'), ('∞', '8'), ('∣', '|'), ('∩', 'n'), ('∶', ':'), ('∼', '~'),
+    ('≡', '='), ('≤', '='), ('≥', '='), ('≪', '«'), ('≫', '»'), ('⌃', '^'),
+    ('⌐', '¬'), ('⌠', '('), ('⌡', ')'), ('〈', '<'), ('〉', '>'), ('─', '-'),
+    ('│', '¦'), ('┌', '+'), ('┐', '+'), ('└', '+'), ('┘', '+'), ('├', '+'),
+    ('┤', '+'), ('┬', '-'), ('┴', '-'), ('┼', '+'), ('═', '-'), ('║', '¦'),
+    ('╒', '+'), ('╓', '+'), ('╔', '+'), ('╕', '+'), ('╖', '+'), ('╗', '+'),
+    ('╘', '+'), ('╙', '+'), ('╚', '+'), ('╛', '+'), ('╜', '+'), ('╝', '+'),
+    ('╞', '¦'), ('╟', '¦'), ('╠', '¦'), ('╡', '¦'), ('╢', '¦'), ('╣', '¦'),
+    ('╤', '-'), ('╥', '-'), ('╦', '-'), ('╧', '-'), ('╨', '-'), ('╩', '-'),
+    ('╪', '+'), ('╫', '+'), ('╬', '+'), ('▀', '-'), ('▄', '_'), ('█', '¦'),
+    ('▌', '¦'), ('▐', '¦'), ('░', '¦'), ('▒', '¦'), ('▓', '¦'), ('■', '¦'),
+    ('☼', '¤'), ('❘', '|'), ('\u{3000}', '\u{20}'), ('〈', '<'), ('〉', '>'),
+    ('《', '«'), ('》', '»'), ('〚', '['), ('〛', ']'), ('・', '·'), ('！', '!'),
+    ('＂', '"'), ('＃', '#'), ('＄', '$'), ('％', '%'), ('＆', '&'), ('＇', '\''),
+    ('（', '('), ('）', ')'), ('＊', '*'), ('＋', '+'), ('，', ','), ('－', '-'),
+    ('．', '.'), ('／', '/'), ('０', '0'), ('１', '1'), ('２', '2'), ('３', '3'),
+    ('４', '4'), ('５', '5'), ('６', '6'), ('７', '7'), ('８', '8'), ('９', '9'),
+    ('：', ':'), ('；', ';'), ('＜', '<'), ('＝', '='), ('＞', '>'), ('？', '?'),
+    ('＠', '@'), ('Ａ', 'A'), ('Ｂ', 'B'), ('Ｃ', 'C'), ('Ｄ', 'D'), ('Ｅ', 'E'),
+    ('Ｆ', 'F'), ('Ｇ', 'G'), ('Ｈ', 'H'), ('Ｉ', 'I'), ('Ｊ', 'J'), ('Ｋ', 'K'),
+    ('Ｌ', 'L'), ('Ｍ', 'M'), ('Ｎ', 'N'), ('Ｏ', 'O'), ('Ｐ', 'P'), ('Ｑ', 'Q'),
+    ('Ｒ', 'R'), ('Ｓ', 'S'), ('Ｔ', 'T'), ('Ｕ', 'U'), ('Ｖ', 'V'), ('Ｗ', 'W'),
+    ('Ｘ', 'X'), ('Ｙ', 'Y'), ('Ｚ', 'Z'), ('［', '['), ('＼', '\\'), ('］', ']'),
+    ('＾', '^'), ('＿', '_'), ('｀', '`'), ('ａ', 'a'), ('ｂ', 'b'), ('ｃ', 'c'),
+    ('ｄ', 'd'), ('ｅ', 'e'), ('ｆ', 'f'), ('ｇ', 'g'), ('ｈ', 'h'), ('ｉ', 'i'),
+    ('ｊ', 'j'), ('ｋ', 'k'), ('ｌ', 'l'), ('ｍ', 'm'), ('ｎ', 'n'), ('ｏ', 'o'),
+    ('ｐ', 'p'), ('ｑ', 'q'), ('ｒ', 'r'), ('ｓ', 's'), ('ｔ', 't'), ('ｕ', 'u'),
+    ('ｖ', 'v'), ('ｗ', 'w'), ('ｘ', 'x'), ('ｙ', 'y'), ('ｚ', 'z'), ('｛', '{'),
+    ('｜', '|'), ('｝', '}'), ('～', '~'),
+];
+
+/// The best fit table of Codepage 1252, from `bestfit1252.txt`.
+pub const CP1252: &[(char, char)] = &[
+    ('Ā', 'A'), ('ā', 'a'), ('Ă', 'A'), ('ă', 'a'), ('Ą', 'A'), ('ą', 'a'),
+    ('Ć', 'C'), ('ć', 'c'), ('Ĉ', 'C'), ('ĉ', 'c'), ('Ċ', 'C'), ('ċ', 'c'),
+    ('Č', 'C'), ('č', 'c'), ('Ď', 'D'), ('ď', 'd'), ('Đ', 'Ð'), ('đ', 'd'),
+    ('Ē', 'E'), ('ē', 'e'), ('Ĕ', 'E'), ('ĕ', 'e'), ('Ė', 'E'), ('ė', 'e'),
+    ('Ę', 'E'), ('ę', 'e'), ('Ě', 'E'), ('ě', 'e'), ('Ĝ', 'G'), ('ĝ', 'g'),
+    ('Ğ', 'G'), ('ğ', 'g'), ('Ġ', 'G'), ('ġ', 'g'), ('Ģ', 'G'), ('ģ', 'g'),
+    ('Ĥ', 'H'), ('ĥ', 'h'), ('Ħ', 'H'), ('ħ', 'h'), ('Ĩ', 'I'), ('ĩ', 'i'),
+    ('Ī', 'I'), ('ī', 'i'), ('Ĭ', 'I'), ('ĭ', 'i'), ('Į', 'I'), ('į', 'i'),
+    ('İ', 'I'), ('ı', 'i'), ('Ĵ', 'J'), ('ĵ', 'j'), ('Ķ', 'K'), ('ķ', 'k'),
+    ('Ĺ', 'L'), ('ĺ', 'l'), ('Ļ', 'L'), ('ļ', 'l'), ('Ľ', 'L'), ('ľ', 'l'),
+    ('Ł', 'L'), ('ł', 'l'), ('Ń', 'N'), ('ń', 'n'), ('Ņ', 'N'), ('ņ', 'n'),
+    ('Ň', 'N'), ('ň', 'n'), ('Ō', 'O'), ('ō', 'o'), ('Ŏ', 'O'), ('ŏ', 'o'),
+    ('Ő', 'O'), ('ő', 'o'), ('Ŕ', 'R'), ('ŕ', 'r'), ('Ŗ', 'R'), ('ŗ', 'r'),
+    ('Ř', 'R'), ('ř', 'r'), ('Ś', 'S'), ('ś', 's'), ('Ŝ', 'S'), ('ŝ', 's'),
+    ('Ş', 'S'), ('ş', 's'), ('Ţ', 'T'), ('ţ', 't'), ('Ť', 'T'), ('ť', 't'),
+    ('Ŧ', 'T'), ('ŧ', 't'), ('Ũ', 'U'), ('ũ', 'u'), ('Ū', 'U'), ('ū', 'u'),
+    ('Ŭ', 'U'), ('ŭ', 'u'), ('Ů', 'U'), ('ů', 'u'), ('Ű', 'U'), ('ű', 'u'),
+    ('Ų', 'U'), ('ų', 'u'), ('Ŵ', 'W'), ('ŵ', 'w'), ('Ŷ', 'Y'), ('ŷ', 'y'),
+    ('Ź', 'Z'), ('ź', 'z'), ('Ż', 'Z'), ('ż', 'z'), ('ƀ', 'b'), ('Ɖ', 'Ð'),
+    ('Ƒ', 'ƒ'), ('Ɨ', 'I'), ('ƚ', 'l'), ('Ɵ', 'O'), ('Ơ', 'O'), ('ơ', 'o'),
+    ('ƫ', 't'), ('Ʈ', 'T'), ('Ư', 'U'), ('ư', 'u'), ('ƶ', 'z'), ('ǀ', '|'),
+    ('ǃ', '!'), ('Ǎ', 'A'), ('ǎ', 'a'), ('Ǐ', 'I'), ('ǐ', 'i'), ('Ǒ', 'O'),
+    ('ǒ', 'o'), ('Ǔ', 'U'), ('ǔ', 'u'), ('Ǖ', 'Ü'), ('ǖ', 'ü'), ('Ǘ', 'Ü'),
+    ('ǘ', 'ü'), ('Ǚ', 'Ü'), ('ǚ', 'ü'), ('Ǜ', 'Ü'), ('ǜ', 'ü'), ('Ǟ', 'Ä'),
+    ('ǟ', 'ä'), ('Ǡ', 'A'), ('ǡ', 'a'), ('Ǣ', 'Æ'), ('ǣ', 'æ'), ('Ǥ', 'G'),
+    ('ǥ', 'g'), ('Ǧ', 'G'), ('ǧ', 'g'), ('Ǩ', 'K'), ('ǩ', 'k'), ('Ǫ', 'O'),
+    ('ǫ', 'o'), ('Ǭ', 'O'), ('ǭ', 'o'), ('ǰ', 'j'), ('Ǵ', 'G'), ('ǵ', 'g'),
+    ('Ǹ', 'N'), ('ǹ', 'n'), ('Ǻ', 'Å'), ('ǻ', 'å'), ('Ǽ', 'Æ'), ('ǽ', 'æ'),
+    ('Ǿ', 'Ø'), ('ǿ', 'ø'), ('Ȁ', 'A'), ('ȁ', 'a'), ('Ȃ', 'A'), ('ȃ', 'a'),
+    ('Ȅ', 'E'), ('ȅ', 'e'), ('Ȇ', 'E'), ('ȇ', 'e'), ('Ȉ', 'I'), ('ȉ', 'i'),
+    ('Ȋ', 'I'), ('ȋ', 'i'), ('Ȍ', 'O'), ('ȍ', 'o'), ('Ȏ', 'O'), ('ȏ', 'o'),
+    ('Ȑ', 'R'), ('ȑ', 'r'), ('Ȓ', 'R'), ('ȓ', 'r'), ('Ȕ', 'U'), ('ȕ', 'u'),
+    ('Ȗ', 'U'), ('ȗ', 'u'), ('Ș', 'S'), ('ș', 's'), ('Ț', 'T'), ('ț', 't'),
+    ('Ȟ', 'H'), ('ȟ', 'h'), ('Ȧ', 'A'), ('ȧ', 'a'), ('Ȩ', 'E'), ('ȩ', 'e'),
+    ('Ȫ', 'Ö'), ('ȫ', 'ö'), ('Ȭ', 'Õ'), ('ȭ', 'õ'), ('Ȯ', 'O'), ('ȯ', 'o'),
+    ('Ȱ', 'O'), ('ȱ', 'o'), ('Ȳ', 'Y'), ('ȳ', 'y'), ('ɡ', 'g'), ('ʹ', '\''),
+    ('ʺ', '"'), ('ʼ', '\''), ('˄', '^'), ('ˈ', '\''), ('ˉ', '¯'), ('ˊ', '´'),
+    ('ˋ', '`'), ('ˍ', '_'), ('˚', '°'), ('\u{300}', '`'), ('\u{301}', '´'),
+    ('\u{302}', '^'), ('\u{303}', '~'), ('\u{304}', '¯'), ('\u{305}', '¯'),
+    ('\u{308}', '¨'), ('\u{30a}', '°'), ('\u{30e}', '"'), ('\u{327}', '¸'),
+    ('\u{331}', '_'), ('\u{332}', '_'), (';', ';'), ('Γ', 'G'), ('Θ', 'T'),
+    ('Σ', 'S'), ('Φ', 'F'), ('Ω', 'O'), ('α', 'a'), ('β', 'ß'), ('δ', 'd'),
+    ('ε', 'e'), ('μ', 'µ'), ('π', 'p'), ('σ', 's'), ('τ', 't'), ('φ', 'f'),
+    ('һ', 'h'), ('։', ':'), ('٪', '%'), ('\u{2000}', '\u{20}'),
+    ('\u{2001}', '\u{20}'), ('\u{2002}', '\u{20}'), ('\u{2003}', '\u{20}'),
+    ('\u{2004}', '\u{20}'), ('\u{2005}', '\u{20}'), ('\u{2006}', '\u{20}'),
+    ('‐', '-'), ('‑', '-'), ('‗', '='), ('′', '\''), ('‵', '`'), ('⁄', '/'),
+    ('⁴', '4'), ('⁵', '5'), ('⁶', '6'), ('⁷', '7'), ('⁸', '8'), ('ⁿ', 'n'),
+    ('₀', '0'), ('₁', '1'), ('₂', '2'), ('₃', '3'), ('₄', '4'), ('₅', '5'),
+    ('₆', '6'), ('₇', '7'), ('₈', '8'), ('₉', '9'), ('₧', 'P'), ('ℂ', 'C'),
+    ('ℇ', 'E'), ('ℊ', 'g'), ('ℋ', 'H'), ('ℌ', 'H'), ('ℍ', 'H'), ('ℎ', 'h'),
+    ('ℐ', 'I'), ('ℑ', 'I'), ('ℒ', 'L'), ('ℓ', 'l'), ('ℕ', 'N'), ('℘', 'P'),
+    ('ℙ', 'P'), ('ℚ', 'Q'), ('ℛ', 'R'), ('ℜ', 'R'), ('ℝ', 'R'), ('ℤ', 'Z'),
+    ('ℨ', 'Z'), ('K', 'K'), ('Å', 'Å'), ('ℬ', 'B'), ('ℭ', 'C'), ('℮', 'e'),
+    ('ℯ', 'e'), ('ℰ', 'E'), ('ℱ', 'F'), ('ℳ', 'M'), ('ℴ', 'o'), ('∅', 'Ø'),
+    ('−', '-'), ('∓', '±'), ('∕', '/'), ('∖', '\\'), ('∗', '*'), ('∘', '°'),
+    ('∙', '·'), ('√', 'v'), ('∞', '8'), ('∣', '|'), ('∩', 'n'), ('∶', ':'),
+    ('∼', '~'), ('≡', '='), ('≤', '='), ('≥', '='), ('≪', '«'), ('≫', '»'),
+    ('⌃', '^'), ('⌐', '¬'), ('⌠', '('), ('⌡', ')'), ('〈', '<'), ('〉', '>'),
+    ('─', '-'), ('│', '¦'), ('┌', '+'), ('┐', '+'), ('└', '+'), ('┘', '+'),
+    ('├', '+'), ('┤', '+'), ('┬', '-'), ('┴', '-'), ('┼', '+'), ('═', '-'),
+    ('║', '¦'), ('╒', '+'), ('╓', '+'), ('╔', '+'), ('╕', '+'), ('╖', '+'),
+    ('╗', '+'), ('╘', '+'), ('╙', '+'), ('╚', '+'), ('╛', '+'), ('╜', '+'),
+    ('╝', '+'), ('╞', '¦'), ('╟', '¦'), ('╠', '¦'), ('╡', '¦'), ('╢', '¦'),
+    ('╣', '¦'), ('╤', '-'), ('╥', '-'), ('╦', '-'), ('╧', '-'), ('╨', '-'),
+    ('╩', '-'), ('╪', '+'), ('╫', '+'), ('╬', '+'), ('▀', '¯'), ('▄', '_'),
+    ('█', '¦'), ('▌', '¦'), ('▐', '¦'), ('░', '¦'), ('▒', '¦'), ('▓', '¦'),
+    ('■', '¦'), ('☼', '¤'), ('❘', '|'), ('\u{3000}', '\u{20}'), ('〈', '<'),
+    ('〉', '>'), ('《', '«'), ('》', '»'), ('〚', '['), ('〛', ']'), ('・', '·'),
+    ('！', '!'), ('＂', '"'), ('＃', '#'), ('＄', '$'), ('％', '%'), ('＆', '&'),
+    ('＇', '\''), ('（', '('), ('）', ')'), ('＊', '*'), ('＋', '+'), ('，', ','),
+    ('－', '-'), ('．', '.'), ('／', '/'), ('０', '0'), ('１', '1'), ('２', '2'),
+    ('３', '3'), ('４', '4'), ('５', '5'), ('６', '6'), ('７', '7'), ('８', '8'),
+    ('９', '9'), ('：', ':'), ('；', ';'), ('＜', '<'), ('＝', '='), ('＞', '>'),
+    ('？', '?'), ('＠', '@'), ('Ａ', 'A'), ('Ｂ', 'B'), ('Ｃ', 'C'), ('Ｄ', 'D'),
+    ('Ｅ', 'E'), ('Ｆ', 'F'), ('Ｇ', 'G'), ('Ｈ', 'H'), ('Ｉ', 'I'), ('Ｊ', 'J'),
+    ('Ｋ', 'K'), ('Ｌ', 'L'), ('Ｍ', 'M'), ('Ｎ', 'N'), ('Ｏ', 'O'), ('Ｐ', 'P'),
+    ('Ｑ', 'Q'), ('Ｒ', 'R'), ('Ｓ', 'S'), ('Ｔ', 'T'), ('Ｕ', 'U'), ('Ｖ', 'V'),
+    ('Ｗ', 'W'), ('Ｘ', 'X'), ('Ｙ', 'Y'), ('Ｚ', 'Z'), ('［', '['), ('＼', '\\'),
+    ('］', ']'), ('＾', '^'), ('＿', '_'), ('｀', '`'), ('ａ', 'a'), ('ｂ', 'b'),
+    ('ｃ', 'c'), ('ｄ', 'd'), ('ｅ', 'e'), ('ｆ', 'f'), ('ｇ', 'g'), ('ｈ', 'h'),
+    ('ｉ', 'i'), ('ｊ', 'j'), ('ｋ', 'k'), ('ｌ', 'l'), ('ｍ', 'm'), ('ｎ', 'n'),
+    ('ｏ', 'o'), ('ｐ', 'p'), ('ｑ', 'q'), ('ｒ', 'r'), ('ｓ', 's'), ('ｔ', 't'),
+    ('ｕ', 'u'), ('ｖ', 'v'), ('ｗ', 'w'), ('ｘ', 'x'), ('ｙ', 'y'), ('ｚ', 'z'),
+    ('｛', '{'), ('｜', '|'), ('｝', '}'), ('～', '~'),
+];
+
+/// The best fit table of Codepage 1253, from `bestfit1253.txt`.
+pub const CP1253: &[(char, char)] = &[
+    ('¢', 'c'), ('ª', 'a'), ('¹', '1'), ('º', 'o'), ('À', 'A'), ('Á', 'A'),
+    ('Â', 'A'), ('Ã', 'A'), ('Ä', 'A'), ('Å', 'A'), ('Æ', 'A'), ('Ç', 'C'),
+    ('È', 'E'), ('É', 'E'), ('Ê', 'E'), ('Ë', 'E'), ('Ì', 'I'), ('Í', 'I'),
+    ('Î', 'I'), ('Ï', 'I'), ('Ð', 'D'), ('Ñ', 'N'), ('Ò', 'O'), ('Ó', 'O'),
+    ('Ô', 'O'), ('Õ', 'O'), ('Ö', 'O'), ('Ø', 'O'), ('Ù', 'U'), ('Ú', 'U'),
+    ('Û', 'U'), ('Ü', 'U'), ('Ý', 'Y'), ('Þ', 'T'), ('à', 'a'), ('á', 'a'),
+    ('â', 'a'), ('ã', 'a'), ('ä', 'a'), ('å', 'a'), ('æ', 'a'), ('ç', 'c'),
+    ('è', 'e'), ('é', 'e'), ('ê', 'e'), ('ë', 'e'), ('ì', 'i'), ('í', 'i'),
+    ('î', 'i'), ('ï', 'i'), ('ð', 'd'), ('ñ', 'n'), ('ò', 'o'), ('ó', 'o'),
+    ('ô', 'o'), ('õ', 'o'), ('ö', 'o'), ('ø', 'o'), ('ù', 'u'), ('ú', 'u'),
+    ('û', 'u'), ('ü', 'u'), ('ý', 'y'), ('þ', 't'), ('ÿ', 'y'), ('Ā', 'A'),
+    ('ā', 'a'), ('Ă', 'A'), ('ă', 'a'), ('Ą', 'A'), ('ą', 'a'), ('Ć', 'C'),
+    ('ć', 'c'), ('Ĉ', 'C'), ('ĉ', 'c'), ('Ċ', 'C'), ('ċ', 'c'), ('Č', 'C'),
+    ('č', 'c'), ('Ď', 'D'), ('ď', 'd'), ('Đ', 'D'), ('đ', 'd'), ('Ē', 'E'),
+    ('ē', 'e'), ('Ĕ', 'E'), ('ĕ', 'e'), ('Ė', 'E'), ('ė', 'e'), ('Ę', 'E'),
+    ('ę', 'e'), ('Ě', 'E'), ('ě', 'e'), ('Ĝ', 'G'), ('ĝ', 'g'), ('Ğ', 'G'),
+    ('ğ', 'g'), ('Ġ', 'G'), ('ġ', 'g'), ('Ģ', 'G'), ('ģ', 'g'), ('Ĥ', 'H'),
+    ('ĥ', 'h'), ('Ħ', 'H'), ('ħ', 'h'), ('Ĩ', 'I'), ('ĩ', 'i'), ('Ī', 'I'),
+    ('ī', 'i'), ('Ĭ', 'I'), ('ĭ', 'i'), ('Į', 'I'), ('į', 'i'), ('İ', 'I'),
+    ('ı', 'i'), ('Ĵ', 'J'), ('ĵ', 'j'), ('Ķ', 'K'), ('ķ', 'k'), ('Ĺ', 'L'),
+    ('ĺ', 'l'), ('Ļ', 'L'), ('ļ', 'l'), ('Ľ', 'L'), ('ľ', 'l'), ('Ł', 'L'),
+    ('ł', 'l'), ('Ń', 'N'), ('ń', 'n'), ('Ņ', 'N'), ('ņ', 'n'), ('Ň', 'N'),
+    ('ň', 'n'), ('Ō', 'O'), ('ō', 'o'), ('Ŏ', 'O'), ('ŏ', 'o'), ('Ő', 'O'),
+    ('ő', 'o'), ('Ŕ', 'R'), ('ŕ', 'r'), ('Ŗ', 'R'), ('ŗ', 'r'), ('Ř', 'R'),
+    ('ř', 'r'), ('Ś', 'S'), ('ś', 's'), ('Ŝ', 'S'), ('ŝ', 's'), ('Ş', 'S'),
+    ('ş', 's'), ('Š', 'S'), ('š', 's'), ('Ţ', 'T'), ('ţ', 't'), ('Ť', 'T'),
+    ('ť', 't'), ('Ŧ', 'T'), ('ŧ', 't'), ('Ũ', 'U'), ('ũ', 'u'), ('Ū', 'U'),
+    ('ū', 'u'), ('Ŭ', 'U'), ('ŭ', 'u'), ('Ů', 'U'), ('ů', 'u'), ('Ű', 'U'),
+    ('ű', 'u'), ('Ų', 'U'), ('ų', 'u'), ('Ŵ', 'W'), ('ŵ', 'w'), ('Ŷ', 'Y'),
+    ('ŷ', 'y'), ('Ÿ', 'Y'), ('Ź', 'Z'), ('ź', 'z'), ('Ż', 'Z'), ('ż', 'z'),
+    ('Ž', 'Z'), ('ž', 'z'), ('ƀ', 'b'), ('Ɖ', 'D'), ('Ƒ', 'ƒ'), ('Ɨ', 'I'),
+    ('ƚ', 'l'), ('Ɵ', 'O'), ('Ơ', 'O'), ('ơ', 'o'), ('ƫ', 't'), ('Ʈ', 'T'),
+    ('Ư', 'U'), ('ư', 'u'), ('ƶ', 'z'), ('ǀ', '|'), ('ǃ', '!'), ('Ǎ', 'A'),
+    ('ǎ', 'a'), ('Ǐ', 'I'), ('ǐ', 'i'), ('Ǒ', 'O'), ('ǒ', 'o'), ('Ǔ', 'U'),
+    ('ǔ', 'u'), ('Ǖ', 'U'), ('ǖ', 'u'), ('Ǘ', 'U'), ('ǘ', 'u'), ('Ǚ', 'U'),
+    ('ǚ', 'u'), ('Ǜ', 'U'), ('ǜ', 'u'), ('Ǟ', 'A'), ('ǟ', 'a'), ('Ǡ', 'A'),
+    ('ǡ', 'a'), ('Ǥ', 'G'), ('ǥ', 'g'), ('Ǧ', 'G'), ('ǧ', 'g'), ('Ǩ', 'K'),
+    ('ǩ', 'k'), ('Ǫ', 'O'), ('ǫ', 'o'), ('Ǭ', 'O'), ('ǭ', 'o'), ('ǰ', 'j'),
+    ('Ǵ', 'G'), ('ǵ', 'g'), ('Ǹ', 'N'), ('ǹ', 'n'), ('Ǻ', 'A'), ('ǻ', 'a'),
+    ('Ȁ', 'A'), ('ȁ', 'a'), ('Ȃ', 'A'), ('ȃ', 'a'), ('Ȅ', 'E'), ('ȅ', 'e'),
+    ('Ȇ', 'E'), ('ȇ', 'e'), ('Ȉ', 'I'), ('ȉ', 'i'), ('Ȋ', 'I'), ('ȋ', 'i'),
+    ('Ȍ', 'O'), ('ȍ', 'o'), ('Ȏ', 'O'), ('ȏ', 'o'), ('Ȑ', 'R'), ('ȑ', 'r'),
+    ('Ȓ', 'R'), ('ȓ', 'r'), ('Ȕ', 'U'), ('ȕ', 'u'), ('Ȗ', 'U'), ('ȗ', 'u'),
+    ('Ș', 'S'), ('ș', 's'), ('Ț', 'T'), ('ț', 't'), ('Ȟ', 'H'), ('ȟ', 'h'),
+    ('Ȧ', 'A'), ('ȧ', 'a'), ('Ȩ', 'E'), ('ȩ', 'e'), ('Ȫ', 'O'), ('ȫ', 'o'),
+    ('Ȭ', 'O'), ('ȭ', 'o'), ('Ȯ', 'O'), ('ȯ', 'o'), ('Ȱ', 'O'), ('ȱ', 'o'),
+    ('Ȳ', 'Y'), ('ȳ', 'y'), ('ɡ', 'g'), ('ʹ', '\''), ('ʺ', '"'), ('ʼ', '\''),
+    ('˄', '^'), ('ˈ', '\''), ('ˉ', '-'), ('ˋ', '`'), ('ˍ', '_'), ('˚', '°'),
+    ('\u{300}', '`'), ('\u{302}', '^'), ('\u{303}', '~'), ('\u{304}', '-'),
+    ('\u{305}', '-'), ('\u{308}', '¨'), ('\u{30a}', '°'), ('\u{30e}', '"'),
+    ('\u{331}', '_'), ('\u{332}', '_'), (';', ';'), ('һ', 'h'), ('։', ':'),
+    ('٪', '%'), ('\u{2000}', '\u{20}'), ('\u{2001}', '\u{20}'),
+    ('\u{2002}', '\u{20}'), ('\u{2003}', '\u{20}'), ('\u{2004}', '\u{20}'),
+    ('\u{2005}', '\u{20}'), ('\u{2006}', '\u{20}'), ('‐', '-'), ('‑', '-'),
+    ('‗', '='), ('′', '\''), ('‵', '`'), ('⁄', '/'), ('⁴', '4'), ('⁵', '5'),
+    ('⁶', '6'), ('⁷', '7'), ('⁸', '8'), ('ⁿ', 'n'), ('₀', '0'), ('₁', '1'),
+    ('₂', '2'), ('₃', '3'), ('₄', '4'), ('₅', '5'), ('₆', '6'), ('₇', '7'),
+    ('₈', '8'), ('₉', '9'), ('₧', 'P'), ('ℂ', 'C'), ('ℇ', 'E'), ('ℊ', 'g'),
+    ('ℋ', 'H'), ('ℌ', 'H'), ('ℍ', 'H'), ('ℎ', 'h'), ('ℐ', 'I'), ('ℑ', 'I'),
+    ('ℒ', 'L'), ('ℓ', 'l'), ('ℕ', 'N'), ('℘', 'P'), ('ℙ', 'P'), ('ℚ', 'Q'),
+    ('ℛ', 'R'), ('ℜ', 'R'), ('ℝ', 'R'), ('ℤ', 'Z'), ('ℨ', 'Z'), ('K', 'K'),
+    ('Å', 'A'), ('ℬ', 'B'), ('ℭ', 'C'), ('℮', 'e'), ('ℯ', 'e'), ('ℰ', 'E'),
+    ('ℱ', 'F'), ('ℳ', 'M'), ('ℴ', 'o'), ('−', '-'), ('∓', '±'), ('∕', '/'),
+    ('∖', '\\'), ('∗', '*'), ('∘', '°'), ('∙', '·'), ('√', 'v'), ('∞', '8'),
+    ('∣', '|'), ('∩', 'n'), ('∶', ':'), ('∼', '~'), ('≡', '='), ('≤', '='),
+    ('≥', '='), ('≪', '«'), ('≫', '»'), ('⌃', '^'), ('⌐', '¬'), ('⌠', '('),
+    ('⌡', ')'), ('〈', '<'), ('〉', '>'), ('─', '-'), ('│', '¦'), ('┌', '+'),
+    ('┐', '+'), ('└', '+'), ('┘', '+'), ('├', '+'), ('┤', '+'), ('┬', '-'),
+    ('┴', '-'), ('┼', '+'), ('═', '-'), ('║', '¦'), ('╒', '+'), ('╓', '+'),
+    ('╔', '+'), ('╕', '+'), ('╖', '+'), ('╗', '+'), ('╘', '+'), ('╙', '+'),
+    ('╚', '+'), ('╛', '+'), ('╜', '+'), ('╝', '+'), ('╞', '¦'), ('╟', '¦'),
+    ('╠', '¦'), ('╡', '¦'), ('╢', '¦'), ('╣', '¦'), ('╤', '-'), ('╥', '-'),
+    ('╦', '-'), ('╧', '-'), ('╨', '-'), ('╩', '-'), ('╪', '+'), ('╫', '+'),
+    ('╬', '+'), ('▀', '-'), ('▄', '_'), ('█', '¦'), ('▌', '¦'), ('▐', '¦'),
+    ('░', '¦'), ('▒', '¦'), ('▓', '¦'), ('■', '¦'), ('☼', '¤'), ('❘', '|'),
+    ('\u{3000}', '\u{20}'), ('〈', '<'), ('〉', '>'), ('《', '«'), ('》', '»'),
+    ('〚', '['), ('〛', ']'), ('・', '·'), ('！', '!'), ('＂', '"'), ('＃', '#'),
+    ('＄', '$'), ('％', '%'), ('＆', '&'), ('＇', '\''), ('（', '('), ('）', ')'),
+    ('＊', '*'), ('＋', '+'), ('，', ','), ('－', '-'), ('．', '.'), ('／', '/'),
+    ('０', '0'), ('１', '1'), ('２', '2'), ('３', '3'), ('４', '4'), ('５', '5'),
+    ('６', '6'), ('７', '7'), ('８', '8'), ('９', '9'), ('：', ':'), ('；', ';'),
+    ('＜', '<'), ('＝', '='), ('＞', '>'), ('？', '?'), ('＠', '@'), ('Ａ', 'A'),
+    ('Ｂ', 'B'), ('Ｃ', 'C'), ('Ｄ', 'D'), ('Ｅ', 'E'), ('Ｆ', 'F'), ('Ｇ', 'G'),
+    ('Ｈ', 'H'), ('Ｉ', 'I'), ('Ｊ', 'J'), ('Ｋ', 'K'), ('Ｌ', 'L'), ('Ｍ', 'M'),
+    ('Ｎ', 'N'), ('Ｏ', 'O'), ('Ｐ', 'P'), ('Ｑ', 'Q'), ('Ｒ', 'R'), ('Ｓ', 'S'),
+    ('Ｔ', 'T'), ('Ｕ', 'U'), ('Ｖ', 'V'), ('Ｗ', 'W'), ('Ｘ', 'X'), ('Ｙ', 'Y'),
+    ('Ｚ', 'Z'), ('［', '['), ('＼', '\\'), ('］', ']'), ('＾', '^'), ('＿', '_'),
+    ('｀', '`'), ('ａ', 'a'), ('ｂ', 'b'), ('ｃ', 'c'), ('ｄ', 'd'), ('ｅ', 'e'),
+    ('ｆ', 'f'), ('ｇ', 'g'), ('ｈ', 'h'), ('ｉ', 'i'), ('ｊ', 'j'), ('ｋ', 'k'),
+    ('ｌ', 'l'), ('ｍ', 'm'), ('ｎ', 'n'), ('ｏ', 'o'), ('ｐ', 'p'), ('ｑ', 'q'),
+    ('ｒ', 'r'), ('ｓ', 's'), ('ｔ', 't'), ('ｕ', 'u'), ('ｖ', 'v'), ('ｗ', 'w'),
+    ('ｘ', 'x'), ('ｙ', 'y'), ('ｚ', 'z'), ('｛', '{'), ('｜', '|'), ('｝', '}'),
+    ('～', '~'),
+];
+
+/// The best fit table of Codepage 1254, from `bestfit1254.txt`.
+pub const CP1254: &[(char, char)] = &[
+    ('Ð', 'D'), ('Ý', 'Y'), ('Þ', 'T'), ('ð', 'd'), ('ý', 'y'), ('þ', 't'),
+    ('Ā', 'A'), ('ā', 'a'), ('Ă', 'A'), ('ă', 'a'), ('Ą', 'A'), ('ą', 'a'),
+    ('Ć', 'C'), ('ć', 'c'), ('Ĉ', 'C'), ('ĉ', 'c'), ('Ċ', 'C'), ('ċ', 'c'),
+    ('Č', 'C'), ('č', 'c'), ('Ď', 'D'), ('ď', 'd'), ('Đ', 'D'), ('đ', 'd'),
+    ('Ē', 'E'), ('ē', 'e'), ('Ĕ', 'E'), ('ĕ', 'e'), ('Ė', 'E'), ('ė', 'e'),
+    ('Ę', 'E'), ('ę', 'e'), ('Ě', 'E'), ('ě', 'e'), ('Ĝ', 'G'), ('ĝ', 'g'),
+    ('Ġ', 'G'), ('ġ', 'g'), ('Ģ', 'G'), ('ģ', 'g'), ('Ĥ', 'H'), ('ĥ', 'h'),
+    ('Ħ', 'H'), ('ħ', 'h'), ('Ĩ', 'I'), ('ĩ', 'i'), ('Ī', 'I'), ('ī', 'i'),
+    ('Ĭ', 'I'), ('ĭ', 'i'), ('Į', 'I'), ('į', 'i'), ('Ĵ', 'J'), ('ĵ', 'j'),
+    ('Ķ', 'K'), ('ķ', 'k'), ('Ĺ', 'L'), ('ĺ', 'l'), ('Ļ', 'L'), ('ļ', 'l'),
+    ('Ľ', 'L'), ('ľ', 'l'), ('Ł', 'L'), ('ł', 'l'), ('Ń', 'N'), ('ń', 'n'),
+    ('Ņ', 'N'), ('ņ', 'n'), ('Ň', 'N'), ('ň', 'n'), ('Ō', 'O'), ('ō', 'o'),
+    ('Ŏ', 'O'), ('ŏ', 'o'), ('Ő', 'O'), ('ő', 'o'), ('Ŕ', 'R'), ('ŕ', 'r'),
+    ('Ŗ', 'R'), ('ŗ', 'r'), ('Ř', 'R'), ('ř', 'r'), ('Ś', 'S'), ('ś', 's'),
+    ('Ŝ', 'S'), ('ŝ', 's'), ('Ţ', 'T'), ('ţ', 't'), ('Ť', 'T'), ('ť', 't'),
+    ('Ŧ', 'T'), ('ŧ', 't'), ('Ũ', 'U'), ('ũ', 'u'), ('Ū', 'U'), ('ū', 'u'),
+    ('Ŭ', 'U'), ('ŭ', 'u'), ('Ů', 'U'), ('ů', 'u'), ('Ű', 'U'), ('ű', 'u'),
+    ('Ų', 'U'), ('ų', 'u'), ('Ŵ', 'W'), ('ŵ', 'w'), ('Ŷ', 'Y'), ('ŷ', 'y'),
+    ('Ź', 'Z'), ('ź', 'z'), ('Ż', 'Z'), ('ż', 'z'), ('Ž', 'Z'), ('ž', 'z'),
+    ('ƀ', 'b'), ('Ɖ', 'D'), ('Ƒ', 'ƒ'), ('Ɨ', 'I'), ('ƚ', 'l'), ('Ɵ', 'O'),
+    ('Ơ', 'O'), ('ơ', 'o'), ('ƫ', 't'), ('Ʈ', 'T'), ('Ư', 'U'), ('ư', 'u'),
+    ('ƶ', 'z'), ('ǀ', '|'), ('ǃ', '!'), ('Ǎ', 'A'), ('ǎ', 'a'), ('Ǐ', 'I'),
+    ('ǐ', 'i'), ('Ǒ', 'O'), ('ǒ', 'o'), ('Ǔ', 'U'), ('ǔ', 'u'), ('Ǖ', 'Ü'),
+    ('ǖ', 'ü'), ('Ǘ', 'Ü'), ('ǘ', 'ü'), ('Ǚ', 'Ü'), ('ǚ', 'ü'), ('Ǜ', 'Ü'),
+    ('ǜ', 'ü'), ('Ǟ', 'Ä'), ('ǟ', 'ä'), ('Ǡ', 'A'), ('ǡ', 'a'), ('Ǣ', 'Æ'),
+    ('ǣ', 'æ'), ('Ǥ', 'G'), ('ǥ', 'g'), ('Ǧ', 'G'), ('ǧ', 'g'), ('Ǩ', 'K'),
+    ('ǩ', 'k'), ('Ǫ', 'O'), ('ǫ', 'o'), ('Ǭ', 'O'), ('ǭ', 'o'), ('ǰ', 'j'),
+    ('Ǵ', 'G'), ('ǵ', 'g'), ('Ǹ', 'N'), ('ǹ', 'n'), ('Ǻ', 'Å'), ('ǻ', 'å'),
+    ('Ǽ', 'Æ'), ('ǽ', 'æ'), ('Ǿ', 'Ø'), ('ǿ', 'ø'), ('Ȁ', 'A'), ('ȁ', 'a'),
+    ('Ȃ', 'A'), ('ȃ', 'a'), ('Ȅ', 'E'), ('ȅ', 'e'), ('Ȇ', 'E'), ('ȇ', 'e'),
+    ('Ȉ', 'I'), ('ȉ', 'i'), ('Ȋ', 'I'), ('ȋ', 'i'), ('Ȍ', 'O'), ('ȍ', 'o'),
+    ('Ȏ', 'O'), ('ȏ', 'o'), ('Ȑ', 'R'), ('ȑ', 'r'), ('Ȓ', 'R'), ('ȓ', 'r'),
+    ('Ȕ', 'U'), ('ȕ', 'u'), ('Ȗ', 'U'), ('ȗ', 'u'), ('Ș', 'S'), ('ș', 's'),
+    ('Ț', 'T'), ('ț', 't'), ('Ȟ', 'H'), ('ȟ', 'h'), ('Ȧ', 'A'), ('ȧ', 'a'),
+    ('Ȩ', 'E'), ('ȩ', 'e'), ('Ȫ', 'Ö'), ('ȫ', 'ö'), ('Ȭ', 'Õ'), ('ȭ', 'õ'),
+    ('Ȯ', 'O'), ('ȯ', 'o'), ('Ȱ', 'O'), ('ȱ', 'o'), ('Ȳ', 'Y'), ('ȳ', 'y'),
+    ('ɡ', 'g'), ('ʹ', '\''), ('ʺ', '"'), ('ʼ', '\''), ('˄', '^'), ('ˈ', '\''),
+    ('ˉ', '¯'), ('ˊ', '´'), ('ˋ', '`'), ('ˍ', '_'), ('˚', '°'),
+    ('\u{300}', '`'), ('\u{301}', '´'), ('\u{302}', '^'), ('\u{303}', '~'),
+    ('\u{304}', '¯'), ('\u{305}', '¯'), ('\u{308}', '¨'), ('\u{30a}', '°'),
+    ('\u{30e}', '"'), ('\u{327}', '¸'), ('\u{331}', '_'), ('\u{332}', '_'),
+    (';', ';'), ('Γ', 'G'), ('Θ', 'T'), ('Σ', 'S'), ('Φ', 'F'), ('Ω', 'O'),
+    ('α', 'a'), ('β', 'ß'), ('δ', 'd'), ('ε', 'e'), ('μ', 'µ'), ('π', 'p'),
+    ('σ', 's'), ('τ', 't'), ('φ', 'f'), ('һ', 'h'), ('։', ':'), ('٪', '%'),
+    ('\u{2000}', '\u{20}'), ('\u{2001}', '\u{20}'), ('\u{2002}', '\u{20}'),
+    ('\u{2003}', '\u{20}'), ('\u{2004}', '\u{20}'), ('\u{2005}', '\u{20}'),
+    ('\u{2006}', '\u{20}'), ('‐', '-'), ('‑', '-'), ('‗', '='), ('′', '\''),
+    ('‵', '`'), ('⁄', '/'), ('⁴', '4'), ('⁵', '5'), ('⁶', '6'), ('⁷', '7'),
+    ('⁸', '8'), ('ⁿ', 'n'), ('₀', '0'), ('₁', '1'), ('₂', '2'), ('₃', '3'),
+    ('₄', '4'), ('₅', '5'), ('₆', '6'), ('₇', '7'), ('₈', '8'), ('₉', '9'),
+    ('₧', 'P'), ('ℂ', 'C'), ('ℇ', 'E'), ('ℊ', 'g'), ('ℋ', 'H'), ('ℌ', 'H'),
+    ('ℍ', 'H'), ('ℎ', 'h'), ('ℐ', 'I'), ('ℑ', 'I'), ('ℒ', 'L'), ('ℓ', 'l'),
+    ('ℕ', 'N'), ('℘', 'P'), ('ℙ', 'P'), ('ℚ', 'Q'), ('ℛ', 'R'), ('ℜ', 'R'),
+    ('ℝ', 'R'), ('ℤ', 'Z'), ('ℨ', 'Z'), ('K', 'K'), ('Å', 'Å'), ('ℬ', 'B'),
+    ('ℭ', 'C'), ('℮', 'e'), ('ℯ', 'e'), ('ℰ', 'E'), ('ℱ', 'F'), ('ℳ', 'M'),
+    ('ℴ', 'o'), ('∅', 'Ø'), ('−', '-'), ('∓', '±'), ('∕', '/'), ('∖', '\\'),
+    ('∗', '*'), ('∘', '°'), ('∙', '·'), ('√', 'v'), ('∞', '8'), ('∣', '|'),
+    ('∩', 'n'), ('∶', ':'), ('∼', '~'), ('≡', '='), ('≤', '='), ('≥', '='),
+    ('≪', '«'), ('≫', '»'), ('⌃', '^'), ('⌐', '¬'), ('⌠', '('), ('⌡', ')'),
+    ('〈', '<'), ('〉', '>'), ('─', '-'), ('│', '¦'), ('┌', '+'), ('┐', '+'),
+    ('└', '+'), ('┘', '+'), ('├', '+'), ('┤', '+'), ('┬', '-'), ('┴', '-'),
+    ('┼', '+'), ('═', '-'), ('║', '¦'), ('╒', '+'), ('╓', '+'), ('╔', '+'),
+    ('╕', '+'), ('╖', '+'), ('╗', '+'), ('╘', '+'), ('╙', '+'), ('╚', '+'),
+    ('╛', '+'), ('╜', '+'), ('╝', '+'), ('╞', '¦'), ('╟', '¦'), ('╠', '¦'),
+    ('╡', '¦'), ('╢', '¦'), ('╣', '¦'), ('╤', '-'), ('╥', '-'), ('╦', '-'),
+    ('╧', '-'), ('╨', '-'), ('╩', '-'), ('╪', '+'), ('╫', '+'), ('╬', '+'),
+    ('▀', '¯'), ('▄', '_'), ('█', '¦'), ('▌', '¦'), ('▐', '¦'), ('░', '¦'),
+    ('▒', '¦'), ('▓', '¦'), ('■', '¦'), ('☼', '¤'), ('❘', '|'),
+    ('\u{3000}', '\u{20}'), ('〈', '<'), ('〉', '>'), ('《', '«'), ('》', '»'),
+    ('〚', '['), ('〛', ']'), ('・', '·'), ('！', '!'), ('＂', '"'), ('＃', '#'),
+    ('＄', '$'), ('％', '%'), ('＆', '&'), ('＇', '\''), ('（', '('), ('）', ')'),
+    ('＊', '*'), ('＋', '+'), ('，', ','), ('－', '-'), ('．', '.'), ('／', '/'),
+    ('０', '0'), ('１', '1'), ('２', '2'), ('３', '3'), ('４', '4'), ('５', '5'),
+    ('６', '6'), ('７', '7'), ('８', '8'), ('９', '9'), ('：', ':'), ('；', ';'),
+    ('＜', '<'), ('＝', '='), ('＞', '>'), ('？', '?'), ('＠', '@'), ('Ａ', 'A'),
+    ('Ｂ', 'B'), ('Ｃ', 'C'), ('Ｄ', 'D'), ('Ｅ', 'E'), ('Ｆ', 'F'), ('Ｇ', 'G'),
+    ('Ｈ', 'H'), ('Ｉ', 'I'), ('Ｊ', 'J'), ('Ｋ', 'K'), ('Ｌ', 'L'), ('Ｍ', 'M'),
+    ('Ｎ', 'N'), ('Ｏ', 'O'), ('Ｐ', 'P'), ('Ｑ', 'Q'), ('Ｒ', 'R'), ('Ｓ', 'S'),
+    ('Ｔ', 'T'), ('Ｕ', 'U'), ('Ｖ', 'V'), ('Ｗ', 'W'), ('Ｘ', 'X'), ('Ｙ', 'Y'),
+    ('Ｚ', 'Z'), ('［', '['), ('＼', '\\'), ('］', ']'), ('＾', '^'), ('＿', '_'),
+    ('｀', '`'), ('ａ', 'a'), ('ｂ', 'b'), ('ｃ', 'c'), ('ｄ', 'd'), ('ｅ', 'e'),
+    ('ｆ', 'f'), ('ｇ', 'g'), ('ｈ', 'h'), ('ｉ', 'i'), ('ｊ', 'j'), ('ｋ', 'k'),
+    ('ｌ', 'l'), ('ｍ', 'm'), ('ｎ', 'n'), ('ｏ', 'o'), ('ｐ', 'p'), ('ｑ', 'q'),
+    ('ｒ', 'r'), ('ｓ', 's'), ('ｔ', 't'), ('ｕ', 'u'), ('ｖ', 'v'), ('ｗ', 'w'),
+    ('ｘ', 'x'), ('ｙ', 'y'), ('ｚ', 'z'), ('｛', '{'), ('｜', '|'), ('｝', '}'),
+    ('～', '~'),
+];
+
+/// The best fit table of Codepage 1255, from `bestfit1255.txt`.
+pub const CP1255: &[(char, char)] = &[
+    ('ª', 'a'), ('º', 'o'), ('À', 'A'), ('Á', 'A'), ('Â', 'A'), ('Ã', 'A'),
+    ('Ä', 'A'), ('Å', 'A'), ('Æ', 'A'), ('Ç', 'C'), ('È', 'E'), ('É', 'E'),
+    ('Ê', 'E'), ('Ë', 'E'), ('Ì', 'I'), ('Í', 'I'), ('Î', 'I'), ('Ï', 'I'),
+    ('Ð', 'D'), ('Ñ', 'N'), ('Ò', 'O'), ('Ó', 'O'), ('Ô', 'O'), ('Õ', 'O'),
+    ('Ö', 'O'), ('Ø', 'O'), ('Ù', 'U'), ('Ú', 'U'), ('Û', 'U'), ('Ü', 'U'),
+    ('Ý', 'Y'), ('Þ', 'T'), ('à', 'a'), ('á', 'a'), ('â', 'a'), ('ã', 'a'),
+    ('ä', 'a'), ('å', 'a'), ('æ', 'a'), ('ç', 'c'), ('è', 'e'), ('é', 'e'),
+    ('ê', 'e'), ('ë', 'e'), ('ì', 'i'), ('í', 'i'), ('î', 'i'), ('ï', 'i'),
+    ('ð', 'd'), ('ñ', 'n'), ('ò', 'o'), ('ó', 'o'), ('ô', 'o'), ('õ', 'o'),
+    ('ö', 'o'), ('ø', 'o'), ('ù', 'u'), ('ú', 'u'), ('û', 'u'), ('ü', 'u'),
+    ('ý', 'y'), ('þ', 't'), ('ÿ', 'y'), ('Ā', 'A'), ('ā', 'a'), ('Ă', 'A'),
+    ('ă', 'a'), ('Ą', 'A'), ('ą', 'a'), ('Ć', 'C'), ('ć', 'c'), ('Ĉ', 'C'),
+    ('ĉ', 'c'), ('Ċ', 'C'), ('ċ', 'c'), ('Č', 'C'), ('č', 'c'), ('Ď', 'D'),
+    ('ď', 'd'), ('Đ', 'D'), ('đ', 'd'), ('Ē', 'E'), ('ē', 'e'), ('Ĕ', 'E'),
+    ('ĕ', 'e'), ('Ė', 'E'), ('ė', 'e'), ('Ę', 'E'), ('ę', 'e'), ('Ě', 'E'),
+    ('ě', 'e'), ('Ĝ', 'G'), ('ĝ', 'g'), ('Ğ', 'G'), ('ğ', 'g'), ('Ġ', 'G'),
+    ('ġ', 'g'), ('Ģ', 'G'), ('ģ', 'g'), ('Ĥ', 'H'), ('ĥ', 'h'), ('Ħ', 'H'),
+    ('ħ', 'h'), ('Ĩ', 'I'), ('ĩ', 'i'), ('Ī', 'I'), ('ī', 'i'), ('Ĭ', 'I'),
+    ('ĭ', 'i'), ('Į', 'I'), ('į', 'i'), ('İ', 'I'), ('ı', 'i'), ('Ĵ', 'J'),
+    ('ĵ', 'j'), ('Ķ', 'K'), ('ķ', 'k'), ('Ĺ', 'L'), ('ĺ', 'l'), ('Ļ', 'L'),
+    ('ļ', 'l'), ('Ľ', 'L'), ('ľ', 'l'), ('Ł', 'L'), ('ł', 'l'), ('Ń', 'N'),
+    ('ń', 'n'), ('Ņ', 'N'), ('ņ', 'n'), ('Ň', 'N'), ('ň', 'n'), ('Ō', 'O'),
+    ('ō', 'o'), ('Ŏ', 'O'), ('ŏ', 'o'), ('Ő', 'O'), ('ő', 'o'), ('Ŕ', 'R'),
+    ('ŕ', 'r'), ('Ŗ', 'R'), ('ŗ', 'r'), ('Ř', 'R'), ('ř', 'r'), ('Ś', 'S'),
+    ('ś', 's'), ('Ŝ', 'S'), ('ŝ', 's'), ('Ş', 'S'), ('ş', 's'), ('Š', 'S'),
+    ('š', 's'), ('Ţ', 'T'), ('ţ', 't'), ('Ť', 'T'), ('ť', 't'), ('Ŧ', 'T'),
+    ('ŧ', 't'), ('Ũ', 'U'), ('ũ', 'u'), ('Ū', 'U'), ('ū', 'u'), ('Ŭ', 'U'),
+    ('ŭ', 'u'), ('Ů', 'U'), ('ů', 'u'), ('Ű', 'U'), ('ű', 'u'), ('Ų', 'U'),
+    ('ų', 'u'), ('Ŵ', 'W'), ('ŵ', 'w'), ('Ŷ', 'Y'), ('ŷ', 'y'), ('Ÿ', 'Y'),
+    ('Ź', 'Z'), ('ź', 'z'), ('Ż', 'Z'), ('ż', 'z'), ('Ž', 'Z'), ('ž', 'z'),
+    ('ƀ', 'b'), ('Ɖ', 'D'), ('Ƒ', 'ƒ'), ('Ɨ', 'I'), ('ƚ', 'l'), ('Ɵ', 'O'),
+    ('Ơ', 'O'), ('ơ', 'o'), ('ƫ', 't'), ('Ʈ', 'T'), ('Ư', 'U'), ('ư', 'u'),
+    ('ƶ', 'z'), ('ǀ', '|'), ('ǃ', '!'), ('Ǎ', 'A'), ('ǎ', 'a'), ('Ǐ', 'I'),
+    ('ǐ', 'i'), ('Ǒ', 'O'), ('ǒ', 'o'), ('Ǔ', 'U'), ('ǔ', 'u'), ('Ǖ', 'U'),
+    ('ǖ', 'u'), ('Ǘ', 'U'), ('ǘ', 'u'), ('Ǚ', 'U'), ('ǚ', 'u'), ('Ǜ', 'U'),
+    ('ǜ', 'u'), ('Ǟ', 'A'), ('ǟ', 'a'), ('Ǡ', 'A'), ('ǡ', 'a'), ('Ǥ', 'G'),
+    ('ǥ', 'g'), ('Ǧ', 'G'), ('ǧ', 'g'), ('Ǩ', 'K'), ('ǩ', 'k'), ('Ǫ', 'O'),
+    ('ǫ', 'o'), ('Ǭ', 'O'), ('ǭ', 'o'), ('ǰ', 'j'), ('Ǵ', 'G'), ('ǵ', 'g'),
+    ('Ǹ', 'N'), ('ǹ', 'n'), ('Ǻ', 'A'), ('ǻ', 'a'), ('Ȁ', 'A'), ('ȁ', 'a'),
+    ('Ȃ', 'A'), ('ȃ', 'a'), ('Ȅ', 'E'), ('ȅ', 'e'), ('Ȇ', 'E'), ('ȇ', 'e'),
+    ('Ȉ', 'I'), ('ȉ', 'i'), ('Ȋ', 'I'), ('ȋ', 'i'), ('Ȍ', 'O'), ('ȍ', 'o'),
+    ('Ȏ', 'O'), ('ȏ', 'o'), ('Ȑ', 'R'), ('ȑ', 'r'), ('Ȓ', 'R'), ('ȓ', 'r'),
+    ('Ȕ', 'U'), ('ȕ', 'u'), ('Ȗ', 'U'), ('ȗ', 'u'), ('Ș', 'S'), ('ș', 's'),
+    ('Ț', 'T'), ('ț', 't'), ('Ȟ', 'H'), ('ȟ', 'h'), ('Ȧ', 'A'), ('ȧ', 'a'),
+    ('Ȩ', 'E'), ('ȩ', 'e'), ('Ȫ', 'O'), ('ȫ', 'o'), ('Ȭ', 'O'), ('ȭ', 'o'),
+    ('Ȯ', 'O'), ('ȯ', 'o'), ('Ȱ', 'O'), ('ȱ', 'o'), ('Ȳ', 'Y'), ('ȳ', 'y'),
+    ('ɡ', 'g'), ('ʹ', '\''), ('ʺ', '"'), ('ʼ', '\''), ('˄', '^'), ('ˈ', '\''),
+    ('ˉ', '¯'), ('ˊ', '´'), ('ˋ', '`'), ('ˍ', '_'), ('˚', '°'),
+    ('\u{300}', '`'), ('\u{301}', '´'), ('\u{302}', '^'), ('\u{303}', '~'),
+    ('\u{304}', '¯'), ('\u{305}', '¯'), ('\u{308}', '¨'), ('\u{30a}', '°'),
+    ('\u{30e}', '"'), ('\u{327}', '¸'), ('\u{331}', '_'), ('\u{332}', '_'),
+    (';', ';'), ('Γ', 'G'), ('Θ', 'T'), ('Σ', 'S'), ('Φ', 'F'), ('Ω', 'O'),
+    ('α', 'a'), ('β', 'b'), ('δ', 'd'), ('ε', 'e'), ('μ', 'µ'), ('π', 'p'),
+    ('σ', 's'), ('τ', 't'), ('φ', 'f'), ('һ', 'h'), ('։', ':'), ('٪', '%'),
+    ('\u{2000}', '\u{20}'), ('\u{2001}', '\u{20}'), ('\u{2002}', '\u{20}'),
+    ('\u{2003}', '\u{20}'), ('\u{2004}', '\u{20}'), ('\u{2005}', '\u{20}'),
+    ('\u{2006}', '\u{20}'), ('‐', '-'), ('‑', '-'), ('‗', '='), ('′', '\''),
+    ('‵', '`'), ('⁄', '/'), ('⁴', '4'), ('⁵', '5'), ('⁶', '6'), ('⁷', '7'),
+    ('⁸', '8'), ('ⁿ', 'n'), ('₀', '0'), ('₁', '1'), ('₂', '2'), ('₃', '3'),
+    ('₄', '4'), ('₅', '5'), ('₆', '6'), ('₇', '7'), ('₈', '8'), ('₉', '9'),
+    ('₧', 'P'), ('ℂ', 'C'), ('ℇ', 'E'), ('ℊ', 'g'), ('ℋ', 'H'), ('ℌ', 'H'),
+    ('ℍ', 'H'), ('ℎ', 'h'), ('ℐ', 'I'), ('ℑ', 'I'), ('ℒ', 'L'), ('ℓ', 'l'),
+    ('ℕ', 'N'), ('℘', 'P'), ('ℙ', 'P'), ('ℚ', 'Q'), ('ℛ', 'R'), ('ℜ', 'R'),
+    ('ℝ', 'R'), ('ℤ', 'Z'), ('ℨ', 'Z'), ('K', 'K'), ('Å', 'A'), ('ℬ', 'B'),
+    ('ℭ', 'C'), ('℮', 'e'), ('ℯ', 'e'), ('ℰ', 'E'), ('ℱ', 'F'), ('ℳ', 'M'),
+    ('ℴ', 'o'), ('−', '-'), ('∓', '±'), ('∕', '/'), ('∖', '\\'), ('∗', '*'),
+    ('∘', '°'), ('∙', '·'), ('√', 'v'), ('∞', '8'), ('∣', '|'), ('∩', 'n'),
+    ('∶', ':'), ('∼', '~'), ('≡', '='), ('≤', '='), ('≥', '='), ('≪', '«'),
+    ('≫', '»'), ('⌃', '^'), ('⌐', '¬'), ('⌠', '('), ('⌡', ')'), ('〈', '<'),
+    ('〉', '>'), ('─', '-'), ('│', '¦'), ('┌', '+'), ('┐', '+'), ('└', '+'),
+    ('┘', '+'), ('├', '+'), ('┤', '+'), ('┬', '-'), ('┴', '-'), ('┼', '+'),
+    ('═', '-'), ('║', '¦'), ('╒', '+'), ('╓', '+'), ('╔', '+'), ('╕', '+'),
+    ('╖', '+'), ('╗', '+'), ('╘', '+'), ('╙', '+'), ('╚', '+'), ('╛', '+'),
+    ('╜', '+'), ('╝', '+'), ('╞', '¦'), ('╟', '¦'), ('╠', '¦'), ('╡', '¦'),
+    ('╢', '¦'), ('╣', '¦'), ('╤', '-'), ('╥', '-'), ('╦', '-'), ('╧', '-'),
+    ('╨', '-'), ('╩', '-'), ('╪', '+'), ('╫', '+'), ('╬', '+'), ('▀', '¯'),
+    ('▄', '_'), ('█', '¦'), ('▌', '¦'), ('▐', '¦'), ('░', '¦'), ('▒', '¦'),
+    ('▓', '¦'), ('■', '¦'), ('☼', '*'), ('❘', '|'), ('\u{3000}', '\u{20}'),
+    ('〈', '<'), ('〉', '>'), ('《', '«'), ('》', '»'), ('〚', '['), ('〛', ']'),
+    ('・', '·'), ('！', '!'), ('＂', '"'), ('＃', '#'), ('＄', '$'), ('％', '%'),
+    ('＆', '&'), ('＇', '\''), ('（', '('), ('）', ')'), ('＊', '*'), ('＋', '+'),
+    ('，', ','), ('－', '-'), ('．', '.'), ('／', '/'), ('０', '0'), ('１', '1'),
+    ('２', '2'), ('３', '3'), ('４', '4'), ('５', '5'), ('６', '6'), ('７', '7'),
+    ('８', '8'), ('９', '9'), ('：', ':'), ('；', ';'), ('＜', '<'), ('＝', '='),
+    ('＞', '>'), ('？', '?'), ('＠', '@'), ('Ａ', 'A'), ('Ｂ', 'B'), ('Ｃ', 'C'),
+    ('Ｄ', 'D'), ('Ｅ', 'E'), ('Ｆ', 'F'), ('Ｇ', 'G'), ('Ｈ', 'H'), ('Ｉ', 'I'),
+    ('Ｊ', 'J'), ('Ｋ', 'K'), ('Ｌ', 'L'), ('Ｍ', 'M'), ('Ｎ', 'N'), ('Ｏ', 'O'),
+    ('Ｐ', 'P'), ('Ｑ', 'Q'), ('Ｒ', 'R'), ('Ｓ', 'S'), ('Ｔ', 'T'), ('Ｕ', 'U'),
+    ('Ｖ', 'V'), ('Ｗ', 'W'), ('Ｘ', 'X'), ('Ｙ', 'Y'), ('Ｚ', 'Z'), ('［', '['),
+    ('＼', '\\'), ('］', ']'), ('＾', '^'), ('＿', '_'), ('｀', '`'), ('ａ', 'a'),
+    ('ｂ', 'b'), ('ｃ', 'c'), ('ｄ', 'd'), ('ｅ', 'e'), ('ｆ', 'f'), ('ｇ', 'g'),
+    ('ｈ', 'h'), ('ｉ', 'i'), ('ｊ', 'j'), ('ｋ', 'k'), ('ｌ', 'l'), ('ｍ', 'm'),
+    ('ｎ', 'n'), ('ｏ', 'o'), ('ｐ', 'p'), ('ｑ', 'q'), ('ｒ', 'r'), ('ｓ', 's'),
+    ('ｔ', 't'), ('ｕ', 'u'), ('ｖ', 'v'), ('ｗ', 'w'), ('ｘ', 'x'), ('ｙ', 'y'),
+    ('ｚ', 'z'), ('｛', '{'), ('｜', '|'), ('｝', '}'), ('～', '~'),
+];
+
+
+/// The default character, used when there is no best fit.
+const DEFAULT: char = '?';
+
+pub struct BestFitEngine<'a, E: Engine + ?Sized>
+{
+    /// The inner engine.
+    pub engine: &'a E,
+    /// The best fit table of the code page of the inner engine, sorted.
+    pub table: &'static [(char, char)]
+}
+
+impl<E: Engine + ?Sized> BestFitEngine<'_, E>
+{
+    /// The best fit for `c`, or the default character.
+    fn best_fit(&self, c: char) -> char
+    {
+        self.table.binary_search_by_key(&c, |&(from, _)| from)
+            .map_or(DEFAULT, |index| self.table[index].1)
+    }
+}
+
+impl<E: Engine + ?Sized> Engine for BestFitEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        format!("{} (best fit)", self.engine.get_name())
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            let mut buf = [0u8; 4];
+            let bytes = match self.engine.encode(c.encode_utf8(&mut buf))
+            {
+                Some(bytes) => bytes,
+                None => self.engine
+                    .encode(self.best_fit(c).encode_utf8(&mut buf))?
+            };
+            encoded.extend(bytes);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        self.engine.decode(bytes)
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        self.engine.try_decode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cp1253engine::CP1253Engine;
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::latin2engine::Latin2Engine;
+
+    #[test]
+    fn encode() {
+        let latin1 = Latin1Engine::new();
+        let engine = BestFitEngine { engine: &latin1, table: CP1252 };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xe9]);
+
+        let encoded = engine.encode("Łódź").unwrap();
+        // Ł => L, ź => z
+        assert_eq!(encoded, b"L\xf3dz");
+
+        let encoded = engine.encode("∞ＡＢ").unwrap();
+        assert_eq!(encoded, b"8AB");
+
+        let encoded = engine.encode("ǘǞ").unwrap();
+        // Keeps the diaeresis, which Codepage 1252 has
+        assert_eq!(encoded, &[0xfc, 0xc4]);
+
+        let encoded = engine.encode("ミク").unwrap();
+        assert_eq!(encoded, b"??");
+
+        let encoded = engine.encode("À").unwrap();
+        assert_eq!(encoded, &[0xc0]);
+
+        let latin2 = Latin2Engine::new();
+        let engine = BestFitEngine { engine: &latin2, table: CP1250 };
+
+        let encoded = engine.encode("Łódź").unwrap();
+        assert_eq!(encoded, &[0xa3, 0xf3, 0x64, 0x9f]);
+
+        let encoded = engine.encode("À").unwrap();
+        assert_eq!(encoded, b"A");
+
+        let cp1253 = CP1253Engine::new();
+        let engine = BestFitEngine { engine: &cp1253, table: CP1253 };
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"e");
+    }
+
+    #[test]
+    fn tables()
+    {
+        for table in [CP1250, CP1252, CP1253, CP1254, CP1255]
+        {
+            assert!(table.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        }
+        assert_eq!(CP1252.iter().find(|(from, _)| *from == 'Ł'),
+            Some(&('Ł', 'L')));
+        assert!(CP1250.iter().all(|(from, _)| *from != 'Ł'));
+    }
+
+    #[test]
+    fn decode()
+    {
+        let latin1 = Latin1Engine::new();
+        let engine = BestFitEngine { engine: &latin1, table: CP1252 };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0x81]);
+        assert_eq!(decoded, "�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let latin1 = Latin1Engine::new();
+        let engine = BestFitEngine { engine: &latin1, table: CP1252 };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
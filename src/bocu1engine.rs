@@ -0,0 +1,362 @@
+//! Deencoding engine for BOCU-1
+//!
+//! The Binary Ordered Compression for Unicode
+//! ([UTN #6](https://www.unicode.org/notes/tn6/)) writes each scalar as the
+//! difference to the previous one, which takes a single byte within a script.
+//! Controls and spaces are written as is; the byte `0xFF` resets the state.
+//!
+//! The previous scalar is not the actual previous scalar, but the middle of
+//! its 128 scalar block, or of the whole script for Hiragana, CJK ideographs
+//! and Hangul, so that differences stay small. Differences take one to four
+//! bytes, with trail bytes avoiding the controls that matter to line based
+//! protocols.
+//!
+//! Decoding replaces invalid trail bytes, truncated sequences and differences
+//! that do not lead to a scalar with U+FFFD � REPLACEMENT CHARACTER, or with
+//! another character configured with [`Bocu1Engine::builder()`]; strict
+//! decoding fails on them instead, but accepts a genuine U+FFFD.
+
+use crate::engine::{name_with_options, Engine};
+
+pub struct Bocu1Engine
+{
+    /// The character to decode invalid sequences as.
+    pub replacement: char
+}
+
+/// Builder for a [`Bocu1Engine`].
+pub struct Bocu1EngineBuilder
+{
+    engine: Bocu1Engine
+}
+
+/// The initial and reset value of the previous scalar.
+const ASCII_PREV: u32 = 0x40;
+
+/// The byte for a difference of 0.
+const MIDDLE: i32 = 0x90;
+
+/// The number of values a trail byte can take.
+const TRAIL_COUNT: i32 = 243;
+
+/// The controls used as trail bytes, for trail values 0 to 19. Trail values
+/// from 20 are the bytes from `0x21`.
+const TRAIL_CONTROLS: [u8; 20] = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x10,
+    0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1c, 0x1d, 0x1e,
+    0x1f];
+
+/// For each length of a multi-byte difference, in number of trail bytes: the
+/// largest positive difference, the first positive lead byte, the smallest
+/// negative difference, and the first negative lead byte.
+const RANGES: [(i32, i32, i32, i32); 3] = [
+    (10512, 0xd0, -10513, 0x50),
+    (187659, 0xfb, -187660, 0x25),
+    (i32::MAX, 0xfe, i32::MIN, 0x22),
+];
+
+/// The previous scalar to use after `scalar`.
+fn prev(scalar: u32) -> u32
+{
+    match scalar
+    {
+        0x3040..=0x309f => 0x3070,
+        0x4e00..=0x9fa5 => 0x7711,
+        0xac00..=0xd7a3 => 0xc1d1,
+        _ => (scalar & !0x7f) + ASCII_PREV
+    }
+}
+
+/// The byte for a trail value.
+fn trail_to_byte(value: i32) -> u8
+{
+    if value < TRAIL_CONTROLS.len() as i32
+    {
+        TRAIL_CONTROLS[value as usize]
+    }
+    else
+    {
+        (value + 0x21 - TRAIL_CONTROLS.len() as i32) as u8
+    }
+}
+
+/// The trail value of a byte, if it is a trail byte.
+fn byte_to_trail(byte: u8) -> Option<i32>
+{
+    if byte >= 0x21
+    {
+        Some(byte as i32 - 0x21 + TRAIL_CONTROLS.len() as i32)
+    }
+    else
+    {
+        TRAIL_CONTROLS.iter().position(|&c| c == byte).map(|p| p as i32)
+    }
+}
+
+/// The bytes for a difference.
+fn pack(diff: i32) -> Vec<u8>
+{
+    if (-64..=63).contains(&diff)
+    {
+        return vec![(MIDDLE + diff) as u8];
+    }
+
+    // Smallest difference of the range, and its lead byte
+    let mut base = if diff > 0 { 64 } else { -64 };
+    for (count, &(max, positive, min, negative)) in RANGES.iter().enumerate()
+    {
+        if diff > max || diff < min
+        {
+            base = if diff > 0 { max + 1 } else { min };
+            continue;
+        }
+        let lead = if diff > 0 { positive } else { negative };
+        let mut rest = diff - base;
+        let mut bytes = vec![0; count + 2];
+        for byte in bytes[1..].iter_mut().rev()
+        {
+            *byte = trail_to_byte(rest.rem_euclid(TRAIL_COUNT));
+            rest = rest.div_euclid(TRAIL_COUNT);
+        }
+        bytes[0] = (lead + rest) as u8;
+        return bytes;
+    }
+    unreachable!()
+}
+
+/// The number of trail bytes, and the difference for trail values of 0, for
+/// a lead byte.
+fn unpack_lead(lead: u8) -> (usize, i32)
+{
+    let lead = lead as i32;
+    let mut base = if lead >= MIDDLE { 64 } else { -64 };
+    for (count, &(max, positive, min, negative)) in RANGES.iter().enumerate()
+    {
+        let next = RANGES.get(count + 1);
+        if lead >= MIDDLE && next.is_none_or(|&(_, p, _, _)| lead < p)
+        {
+            let width = TRAIL_COUNT.pow(count as u32 + 1);
+            return (count + 1, base + (lead - positive) * width);
+        }
+        if lead < MIDDLE && next.is_none_or(|&(_, _, _, n)| lead >= n)
+        {
+            let width = TRAIL_COUNT.pow(count as u32 + 1);
+            return (count + 1, base + (lead - negative) * width);
+        }
+        base = if lead >= MIDDLE { max + 1 } else { min };
+    }
+    unreachable!()
+}
+
+impl Bocu1Engine
+{
+    /// The default engine, decoding invalid sequences as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Bocu1Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Bocu1EngineBuilder
+    {
+        Bocu1EngineBuilder { engine: Bocu1Engine::new() }
+    }
+
+    /// Decode, with invalid sequences as `replacement`, or failing without
+    /// one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let mut decoded = String::new();
+        let mut previous = ASCII_PREV;
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            let b = bytes[i];
+            i += 1;
+            if b <= 0x20
+            {
+                if b != 0x20
+                {
+                    previous = ASCII_PREV;
+                }
+                decoded.push(b as char);
+                continue;
+            }
+            if b == 0xff
+            {
+                // Reset
+                previous = ASCII_PREV;
+                continue;
+            }
+
+            let diff = if (MIDDLE - 64..MIDDLE + 64).contains(&(b as i32))
+            {
+                b as i32 - MIDDLE
+            }
+            else
+            {
+                let (count, base) = unpack_lead(b);
+                let trails: Option<Vec<i32>> = bytes.get(i..i + count)
+                    .and_then(|trails| trails.iter()
+                        .map(|&t| byte_to_trail(t))
+                        .collect());
+                let Some(trails) = trails
+                else
+                {
+                    // Invalid or missing trail bytes, which are read again
+                    decoded.push(replacement?);
+                    continue;
+                };
+                i += count;
+                base + trails.iter().fold(0, |acc, t| acc * TRAIL_COUNT + t)
+            };
+
+            match u32::try_from(previous as i32 + diff).ok()
+                .and_then(char::from_u32)
+            {
+                Some(c) =>
+                {
+                    decoded.push(c);
+                    previous = prev(c as u32);
+                },
+                None => decoded.push(replacement?)
+            }
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Default for Bocu1Engine
+{
+    fn default() -> Self { Bocu1Engine::new() }
+}
+
+impl Bocu1EngineBuilder
+{
+    /// Set the character to decode invalid sequences as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Bocu1Engine { self.engine }
+}
+
+impl Engine for Bocu1Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("BOCU-1", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+        let mut previous = ASCII_PREV;
+
+        for c in string.chars()
+        {
+            let scalar = c as u32;
+            if scalar <= 0x20
+            {
+                if scalar != 0x20
+                {
+                    previous = ASCII_PREV;
+                }
+                encoded.push(scalar as u8);
+                continue;
+            }
+            encoded.extend(pack(scalar as i32 - previous as i32));
+            previous = prev(scalar);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        Bocu1Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Bocu1Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = Bocu1Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x98, 0xb5, 0xbc, 0xbc, 0xbf]);
+
+        let encoded = engine.encode("a\nb c").unwrap();
+        assert_eq!(encoded, &[0xb1, 0x0a, 0xb2, 0x20, 0xb3]);
+
+        let encoded = engine.encode("é").unwrap();
+        // 0xE9 - 0x40 = 169 = 64 + 105
+        assert_eq!(encoded, &[0xd0, 0x76]);
+
+        let encoded = engine.encode("\u{feff}").unwrap();
+        // Signature byte sequence
+        assert_eq!(encoded, &[0xfb, 0xee, 0x28]);
+
+        // Round trip, through all lengths
+        let string = "Clément Москва 東京 한국어 ミク \u{10ffff}😀a\u{1}";
+        assert_eq!(engine.decode(&engine.encode(string).unwrap()), string);
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = Bocu1Engine::new();
+
+        let decoded = engine.decode(&[0xc7, 0xbf, 0xc2, 0xbc, 0xb4, 0x71]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xd0, 0x76, 0xff, 0x91]);
+        assert_eq!(decoded, "éA");
+
+        let decoded = engine.decode(&[0xd0, 0x0a, 0xd0]);
+        // Invalid trail byte, truncated sequence
+        assert_eq!(decoded, "�\n�");
+
+        assert!(engine.try_decode(&[0xd0, 0x0a, 0xd0]).is_none());
+
+        let encoded = engine.encode("\u{fffd}").unwrap();
+        assert_eq!(engine.try_decode(&encoded).unwrap(), "�");
+
+        let engine = Bocu1Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "BOCU-1 (errors as '?')");
+
+        let decoded = engine.decode(&[0xd0, 0x0a, 0xd0]);
+        assert_eq!(decoded, "?\n?");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Bocu1Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
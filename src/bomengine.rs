@@ -0,0 +1,241 @@
+//! Deencoding engine adding byte order mark handling to another engine
+//!
+//! A byte order mark (BOM) is U+FEFF ZERO WIDTH NO-BREAK SPACE at the start of
+//! a file, which tells its encoding: `EF BB BF` for UTF-8, `FE FF` and `FF FE`
+//! for UTF-16BE and UTF-16LE, `00 00 FE FF` and `FF FE 00 00` for UTF-32BE and
+//! UTF-32LE. Readers that do not expect it show `"ï»¿"` in Codepage 1252,
+//! while readers that do switch encodings when they see one, whatever they
+//! were told the file was.
+//!
+//! This engine wraps another engine. Encoding adds a BOM through the inner
+//! engine, and fails if it cannot encode U+FEFF. Decoding sniffs a BOM, like
+//! browsers and `Encoding::for_bom()` of `encoding_rs` do, extended to UTF-32:
+//! if there is one, the rest is decoded with the matching encoding, otherwise
+//! everything is decoded with the inner engine. The UTF-32LE mark starts with
+//! the UTF-16LE one, followed by U+0000 in UTF-16LE, so it is only taken as
+//! such when the rest is valid UTF-32LE.
+//!
+//! The UTF-8 and UTF-16 engines sniff byte order marks the same way when
+//! configured with a byte order mark, and decode the rest with their own
+//! configuration when the mark is theirs.
+
+use crate::engine::Engine;
+use crate::utf16engine::{Endianness, Utf16Engine};
+use crate::utf8engine::Utf8Engine;
+
+/// The byte order mark.
+const BOM: char = '\u{feff}';
+
+pub struct BomEngine<'a, E: Engine + ?Sized>
+{
+    /// The inner engine.
+    pub engine: &'a E
+}
+
+/// Decode UTF-32, replacing invalid scalars and a trailing incomplete unit
+/// with `replacement`, or failing without one.
+fn decode_utf32(bytes: &[u8], big_endian: bool, replacement: Option<char>)
+    -> Option<String>
+{
+    let chunks = bytes.chunks_exact(4);
+    let orphan = !chunks.remainder().is_empty();
+
+    let mut decoded = chunks
+        .map(|unit|
+        {
+            let unit = unit.try_into().unwrap();
+            let scalar = if big_endian
+            {
+                u32::from_be_bytes(unit)
+            }
+            else
+            {
+                u32::from_le_bytes(unit)
+            };
+            char::from_u32(scalar).or(replacement)
+        })
+        .collect::<Option<String>>()?;
+    if orphan
+    {
+        decoded.push(replacement?);
+    }
+    Some(decoded)
+}
+
+/// A byte order mark, telling the encoding of the rest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Bom
+{
+    Utf8,
+    Utf16BE,
+    Utf16LE,
+    Utf32BE,
+    Utf32LE
+}
+
+/// Sniff a byte order mark at the start of `bytes`, giving it and the rest.
+/// The UTF-32LE mark is only taken as such when the rest is valid UTF-32LE,
+/// and not UTF-16LE starting with U+0000.
+pub(crate) fn sniff(bytes: &[u8]) -> Option<(Bom, &[u8])>
+{
+    // UTF-32LE starts like UTF-16LE, so it goes first
+    let utf32le = bytes.strip_prefix(&[0xff, 0xfe, 0x00, 0x00])
+        .filter(|rest| rest.len() % 4 == 0
+            && decode_utf32(rest, false, None).is_some());
+    if let Some(rest) = utf32le
+    {
+        return Some((Bom::Utf32LE, rest));
+    }
+    let marks: [(&[u8], Bom); 4] = [
+        (&[0x00, 0x00, 0xfe, 0xff], Bom::Utf32BE),
+        (&[0xef, 0xbb, 0xbf], Bom::Utf8),
+        (&[0xfe, 0xff], Bom::Utf16BE),
+        (&[0xff, 0xfe], Bom::Utf16LE),
+    ];
+    marks.into_iter()
+        .find_map(|(mark, bom)| Some((bom, bytes.strip_prefix(mark)?)))
+}
+
+/// Decode `bytes` with the encoding of `bom`, decoding invalid sequences as
+/// `replacement`, or failing without one.
+pub(crate) fn decode_after(bom: Bom, bytes: &[u8], replacement: Option<char>)
+    -> Option<String>
+{
+    let lossy = replacement.unwrap_or(char::REPLACEMENT_CHARACTER);
+    let engine: &dyn Engine = match bom
+    {
+        Bom::Utf8 => &Utf8Engine::builder().replacement(lossy).build(),
+        Bom::Utf16BE => &Utf16Engine::builder().replacement(lossy).build(),
+        Bom::Utf16LE => &Utf16Engine::builder()
+            .endianness(Endianness::Little)
+            .replacement(lossy)
+            .build(),
+        Bom::Utf32BE => return decode_utf32(bytes, true, replacement),
+        Bom::Utf32LE => return decode_utf32(bytes, false, replacement)
+    };
+    match replacement
+    {
+        Some(_) => Some(engine.decode(bytes)),
+        None => engine.try_decode(bytes)
+    }
+}
+
+impl<E: Engine + ?Sized> Engine for BomEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        format!("{} (with BOM)", self.engine.get_name())
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        self.engine.encode(&format!("{}{}", BOM, string))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        match sniff(bytes)
+        {
+            // Cannot fail with a replacement
+            Some((bom, rest)) =>
+                decode_after(bom, rest, Some(char::REPLACEMENT_CHARACTER))
+                    .unwrap(),
+            None => self.engine.decode(bytes)
+        }
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        match sniff(bytes)
+        {
+            Some((bom, rest)) => decode_after(bom, rest, None),
+            None => self.engine.try_decode(bytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+
+    #[test]
+    fn encode() {
+        let engine = BomEngine { engine: &Utf8Engine::new() };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0xef, 0xbb, 0xbf, 0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+        assert_eq!(Latin1Engine::new().decode(&encoded), "ï»¿Hello");
+
+        let engine = BomEngine {
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xff, 0xfe, 0xe9, 0x00]);
+
+        let encoded = engine.encode("\0é").unwrap();
+        assert_eq!(encoded, &[0xff, 0xfe, 0x00, 0x00, 0xe9, 0x00]);
+        assert_eq!(engine.decode(&encoded), "\0é");
+
+        let engine = BomEngine { engine: &Latin1Engine::new() };
+        assert_eq!(engine.get_name(), "Latin-1 / Codepage 1252 (with BOM)");
+
+        assert!(engine.encode("é").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = BomEngine { engine: &Latin1Engine::new() };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xef, 0xbb, 0xbf, 0xc3, 0xa9]);
+        assert_eq!(decoded, "é");
+
+        let decoded = engine.decode(&[0xfe, 0xff, 0x00, 0xe9]);
+        assert_eq!(decoded, "é");
+
+        let decoded = engine.decode(&[0xff, 0xfe, 0xe9, 0x00]);
+        assert_eq!(decoded, "é");
+
+        let decoded = engine.decode(&[0xff, 0xfe, 0x00, 0x00, 0x00, 0xf6, 0x01,
+            0x00]);
+        assert_eq!(decoded, "😀");
+
+        let decoded = engine.decode(&[0xff, 0xfe, 0x00, 0x00, 0xe9, 0x00]);
+        // Not UTF-32LE, but UTF-16LE starting with U+0000
+        assert_eq!(decoded, "\0é");
+
+        let decoded = engine.decode(&[0xff, 0xfe, 0x00, 0x00, 0x00, 0xd8, 0x00,
+            0x00]);
+        // Not a scalar in UTF-32LE, but U+0000 U+D800 U+0000 in UTF-16LE
+        assert_eq!(decoded, "\0�\0");
+
+        assert!(engine.try_decode(&[0xff, 0xfe, 0x00, 0x00, 0x00, 0xd8, 0x00,
+            0x00]).is_none());
+        assert!(engine.try_decode(&[0x81]).is_none());
+
+        let decoded = engine.try_decode(&[0xef, 0xbb, 0xbf, 0xc3, 0xa9]);
+        assert_eq!(decoded.unwrap(), "é");
+
+        let decoded = engine.decode(&[0x00, 0x00, 0xfe, 0xff, 0x00, 0x00, 0x00,
+            0xe9]);
+        assert_eq!(decoded, "é");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = BomEngine { engine: &Utf8Engine::new() };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -0,0 +1,162 @@
+//! Deencoding engine for C-style `\xNN` escapes over bytes
+//!
+//! Unlike most engines, this one represents bytes as text, the way a C string
+//! literal or a Python `bytes` repr does: decoding keeps printable ASCII bytes
+//! and writes every other byte as a `\xNN` escape, so the UTF-8 encoding of
+//! `"Clément"` is decoded as `"Cl\xc3\xa9ment"`. Backslashes are escaped as
+//! `\\`, and the usual `\n`, `\r` and `\t` are used.
+//!
+//! Encoding parses such a literal back into bytes. It also accepts octal
+//! escapes and the other single character escapes of C; scalars outside of
+//! ASCII are written as UTF-8, like compilers do by default. Encoding fails on
+//! malformed or unknown escapes.
+
+use crate::engine::Engine;
+
+pub struct CEscapeEngine {}
+
+impl Engine for CEscapeEngine
+{
+    fn get_name(&self) -> String { "C escapes".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        let mut chars = string.chars().peekable();
+        while let Some(c) = chars.next()
+        {
+            if c != '\\'
+            {
+                let mut buf = [0u8; 4];
+                encoded.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                continue;
+            }
+            let byte = match chars.next()?
+            {
+                'a' => 0x07,
+                'b' => 0x08,
+                'f' => 0x0c,
+                'n' => b'\n',
+                'r' => b'\r',
+                't' => b'\t',
+                'v' => 0x0b,
+                e @ ('\\' | '\'' | '"' | '?') => e as u8,
+                'x' =>
+                {
+                    let mut value = 0u8;
+                    let mut digits = 0;
+                    while let Some(d) =
+                        chars.peek().and_then(|d| d.to_digit(16))
+                    {
+                        if digits == 2
+                        {
+                            break;
+                        }
+                        value = value * 16 + d as u8;
+                        digits += 1;
+                        chars.next();
+                    }
+                    if digits == 0
+                    {
+                        return None;
+                    }
+                    value
+                }
+                d @ '0'..='7' =>
+                {
+                    let mut value = d.to_digit(8)?;
+                    let mut digits = 1;
+                    while let Some(d) =
+                        chars.peek().and_then(|d| d.to_digit(8))
+                    {
+                        if digits == 3
+                        {
+                            break;
+                        }
+                        value = value * 8 + d;
+                        digits += 1;
+                        chars.next();
+                    }
+                    u8::try_from(value).ok()?
+                }
+                _ => return None
+            };
+            encoded.push(byte);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let mut decoded = String::new();
+
+        for &b in bytes
+        {
+            match b
+            {
+                b'\\' => decoded.push_str("\\\\"),
+                b'\n' => decoded.push_str("\\n"),
+                b'\r' => decoded.push_str("\\r"),
+                b'\t' => decoded.push_str("\\t"),
+                0x20..=0x7e => decoded.push(b as char),
+                _ => decoded.push_str(&format!("\\x{:02x}", b))
+            }
+        }
+
+        decoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = CEscapeEngine{};
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("\\xe9").unwrap();
+        assert_eq!(encoded, &[0xe9]);
+
+        let encoded = engine.encode("\\351\\n").unwrap();
+        assert_eq!(encoded, &[0xe9, 0x0a]);
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xc3, 0xa9]);
+
+        assert!(engine.encode("\\q").is_none());
+        assert!(engine.encode("\\x").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = CEscapeEngine{};
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0x43, 0x6c, 0xc3, 0xa9, 0x6d]);
+        assert_eq!(decoded, "Cl\\xc3\\xa9m");
+
+        let decoded = engine.decode(b"a\\b\n");
+        assert_eq!(decoded, "a\\\\b\\n");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = CEscapeEngine{};
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+    }
+}
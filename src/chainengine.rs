@@ -0,0 +1,167 @@
+//! Deencoding engine running several engines as a single step
+//!
+//! Some systems stack transformations: the UTF-8 bytes of a string are then
+//! Base64 encoded, or the Codepage 1252 bytes are percent-encoded. This engine
+//! runs a list of engines in a row, as a single step of the tree.
+//!
+//! The bytes produced by an engine are handed to the next one as a "binary
+//! string", with one scalar per byte (Latin-1), which is how most systems feed
+//! bytes to a text-based transformation: chaining UTF-8 then
+//! [JSON](crate::jsonengine) encodes `"é"` as `"\u00c3\u00a9"`. Decoding runs
+//! the engines in reverse order, and turns each string back into bytes the
+//! same way, replacing scalars that do not fit in a byte with `'?'`.
+
+use crate::engine::Engine;
+
+pub struct ChainEngine<'a>
+{
+    /// The engines, in encoding order.
+    pub engines: Vec<&'a dyn Engine>
+}
+
+/// Turn bytes into a binary string.
+fn to_binary_string(bytes: &[u8]) -> String
+{
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+/// Turn a binary string back into bytes.
+fn from_binary_string(string: &str) -> Vec<u8>
+{
+    string.chars()
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Turn a binary string back into bytes, failing on scalars above `0xFF`.
+fn try_from_binary_string(string: &str) -> Option<Vec<u8>>
+{
+    string.chars()
+        .map(|c| u8::try_from(c).ok())
+        .collect()
+}
+
+impl Engine for ChainEngine<'_>
+{
+    fn get_name(&self) -> String
+    {
+        self.engines.iter()
+            .map(|engine| engine.get_name())
+            .collect::<Vec<_>>()
+            .join(" then ")
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut engines = self.engines.iter();
+        let mut encoded = match engines.next()
+        {
+            Some(engine) => engine.encode(string)?,
+            None => Vec::from(string.as_bytes())
+        };
+        for engine in engines
+        {
+            encoded = engine.encode(&to_binary_string(&encoded))?;
+        }
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let mut engines = self.engines.iter().rev();
+        let mut decoded = match engines.next()
+        {
+            Some(engine) => engine.decode(bytes),
+            None => String::from_utf8_lossy(bytes).into_owned()
+        };
+        for engine in engines
+        {
+            decoded = engine.decode(&from_binary_string(&decoded));
+        }
+        decoded
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let mut engines = self.engines.iter().rev();
+        let mut decoded = match engines.next()
+        {
+            Some(engine) => engine.try_decode(bytes)?,
+            None => String::from_utf8(bytes.to_vec()).ok()?
+        };
+        for engine in engines
+        {
+            decoded = engine.try_decode(&try_from_binary_string(&decoded)?)?;
+        }
+        Some(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cp1253engine::CP1253Engine;
+    use crate::engine::conformance;
+    use crate::jsonengine::JsonEngine;
+    use crate::latin1engine::Latin1Engine;
+    use crate::pythonengine::PythonEngine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let utf8 = Utf8Engine::new();
+        let engine = ChainEngine { engines: vec![&utf8, &JsonEngine{}] };
+
+        assert_eq!(engine.get_name(), "UTF-8 then JSON escapes");
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"\\u00c3\\u00a9");
+
+        let latin1 = Latin1Engine::new();
+        let engine = ChainEngine { engines: vec![&latin1, &PythonEngine{}] };
+
+        let encoded = engine.encode("Clément").unwrap();
+        assert_eq!(encoded, b"Cl\\xe9ment");
+
+        assert!(engine.encode("ミク").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let utf8 = Utf8Engine::new();
+        let engine = ChainEngine { engines: vec![&utf8, &JsonEngine{}] };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"\\u00c3\\u00a9");
+        assert_eq!(decoded, "é");
+
+        let decoded = engine.decode(b"\\u00e9\\u03b1");
+        // U+03B1 does not fit in a byte
+        assert_eq!(decoded, "�?");
+
+        let cp1253 = CP1253Engine::new();
+        let engine = ChainEngine { engines: vec![&cp1253, &PythonEngine{}] };
+
+        let decoded = engine.decode(b"\\xe1\\xe2\\xe3");
+        assert_eq!(decoded, "αβγ");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let utf8 = Utf8Engine::new();
+        let engine = ChainEngine { engines: vec![&utf8, &JsonEngine{}] };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -5,17 +5,83 @@
 //! support insertion of U+FFFD � REPLACEMENT CHARACTER at decoding. Decoding is
 //! performed with [`mail-parser`](https://crates.io/crates/mail-parser), which
 //! does not allow encoding.
+//!
+//! The undefined bytes are decoded as U+FFFD � REPLACEMENT CHARACTER by
+//! default, or as another character configured with
+//! [`CP1255Engine::builder()`].
 
-use crate::engine::Engine;
+use crate::engine::{name_with_options, Engine};
 
 use encoding_rs::*;
 use mail_parser::*;
 
-pub struct CP1255Engine {}
+pub struct CP1255Engine
+{
+    /// The character to decode undefined bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`CP1255Engine`].
+pub struct CP1255EngineBuilder
+{
+    engine: CP1255Engine
+}
+
+impl CP1255Engine
+{
+    /// The default engine, decoding undefined bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        CP1255Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> CP1255EngineBuilder
+    {
+        CP1255EngineBuilder { engine: CP1255Engine::new() }
+    }
+
+    /// Decode, with undefined bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        decoders::charsets::single_byte::decoder_cp1255(bytes)
+            .chars()
+            .map(|c|
+                if c == char::REPLACEMENT_CHARACTER { replacement }
+                else { Some(c) })
+            .collect()
+    }
+}
+
+impl Default for CP1255Engine
+{
+    fn default() -> Self { CP1255Engine::new() }
+}
+
+impl CP1255EngineBuilder
+{
+    /// Set the character to decode undefined bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> CP1255Engine { self.engine }
+}
 
 impl Engine for CP1255Engine
 {
-    fn get_name(&self) -> String { "ISO 8859-8 / Codepage 1255".to_string() }
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("ISO 8859-8 / Codepage 1255", &options)
+    }
 
     fn encode(&self, string: &str) -> Option<Vec<u8>>
     {
@@ -32,7 +98,13 @@ impl Engine for CP1255Engine
 
     fn decode(&self, bytes: &[u8]) -> String
     {
-        decoders::charsets::single_byte::decoder_cp1255(bytes)
+        // Cannot fail with a replacement
+        CP1255Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        CP1255Engine::decode_with(bytes, None)
     }
 }
 
@@ -40,9 +112,11 @@ impl Engine for CP1255Engine
 mod tests {
     use super::*;
 
+    use crate::engine::conformance;
+
     #[test]
     fn encode() {
-        let engine = CP1255Engine{};
+        let engine = CP1255Engine::new();
 
         let encoded = engine.encode("Hello").unwrap();
         assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
@@ -56,7 +130,7 @@ mod tests {
     #[test]
     fn decode()
     {
-        let engine = CP1255Engine{};
+        let engine = CP1255Engine::new();
 
         let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
         assert_eq!(decoded, "world!");
@@ -66,5 +140,26 @@ mod tests {
 
         let decoded = engine.decode(&[0x81]);
         assert_eq!(decoded, "�");
+
+        let engine = CP1255Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(),
+            "ISO 8859-8 / Codepage 1255 (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x81, 0x42]);
+        assert_eq!(decoded, "A?B");
+
+        assert!(engine.try_decode(&[0x41, 0x81, 0x42]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = CP1255Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
     }
 }
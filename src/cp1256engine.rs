@@ -0,0 +1,98 @@
+//! Deencoding engine for Codepage 1256
+//!
+//! Encoding is performed with
+//! [`encoding_rs`](https://crates.io/crates/encoding_rs), which does not
+//! support insertion of U+FFFD � REPLACEMENT CHARACTER at decoding. Decoding is
+//! performed with [`mail-parser`](https://crates.io/crates/mail-parser), which
+//! does not allow encoding.
+//!
+//! Every byte is defined, so unlike the other code page engines, there are no
+//! undefined bytes to configure a replacement for.
+
+use crate::engine::Engine;
+
+use encoding_rs::*;
+use mail_parser::*;
+
+pub struct CP1256Engine {}
+
+impl CP1256Engine
+{
+    /// The engine.
+    pub const fn new() -> Self { CP1256Engine {} }
+}
+
+impl Default for CP1256Engine
+{
+    fn default() -> Self { CP1256Engine::new() }
+}
+
+impl Engine for CP1256Engine
+{
+    fn get_name(&self) -> String { "Codepage 1256".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let (output, _, error) = WINDOWS_1256.encode(string);
+        if error
+        {
+            None
+        }
+        else
+        {
+            Some(output.into_owned())
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        decoders::charsets::single_byte::decoder_cp1256(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = CP1256Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("ب").unwrap();
+        assert_eq!(encoded, &[0xc8]);
+
+        assert!(engine.encode("😀").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = CP1256Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe1]);
+        assert_eq!(decoded, "ل");
+
+        let decoded = engine.decode(&[0x81]);
+        assert_eq!(decoded, "پ");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = CP1256Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
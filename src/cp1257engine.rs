@@ -0,0 +1,164 @@
+//! Deencoding engine for Codepage 1257
+//!
+//! Encoding is performed with
+//! [`encoding_rs`](https://crates.io/crates/encoding_rs), which does not
+//! support insertion of U+FFFD � REPLACEMENT CHARACTER at decoding. Decoding is
+//! performed with [`mail-parser`](https://crates.io/crates/mail-parser), which
+//! does not allow encoding.
+//!
+//! The undefined bytes are decoded as U+FFFD � REPLACEMENT CHARACTER by
+//! default, or as another character configured with
+//! [`CP1257Engine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+use encoding_rs::*;
+use mail_parser::*;
+
+pub struct CP1257Engine
+{
+    /// The character to decode undefined bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`CP1257Engine`].
+pub struct CP1257EngineBuilder
+{
+    engine: CP1257Engine
+}
+
+impl CP1257Engine
+{
+    /// The default engine, decoding undefined bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        CP1257Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> CP1257EngineBuilder
+    {
+        CP1257EngineBuilder { engine: CP1257Engine::new() }
+    }
+
+    /// Decode, with undefined bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        decoders::charsets::single_byte::decoder_cp1257(bytes)
+            .chars()
+            .map(|c|
+                if c == char::REPLACEMENT_CHARACTER { replacement }
+                else { Some(c) })
+            .collect()
+    }
+}
+
+impl Default for CP1257Engine
+{
+    fn default() -> Self { CP1257Engine::new() }
+}
+
+impl CP1257EngineBuilder
+{
+    /// Set the character to decode undefined bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> CP1257Engine { self.engine }
+}
+
+impl Engine for CP1257Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("Codepage 1257", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let (output, _, error) = WINDOWS_1257.encode(string);
+        if error
+        {
+            None
+        }
+        else
+        {
+            Some(output.into_owned())
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        CP1257Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        CP1257Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = CP1257Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("ą").unwrap();
+        assert_eq!(encoded, &[0xe0]);
+
+        assert!(engine.encode("😀").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = CP1257Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "č");
+
+        let decoded = engine.decode(&[0x81]);
+        assert_eq!(decoded, "�");
+
+        let engine = CP1257Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "Codepage 1257 (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x81, 0x42]);
+        assert_eq!(decoded, "A?B");
+
+        assert!(engine.try_decode(&[0x41, 0x81, 0x42]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = CP1257Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,225 @@
+//! Deencoding engine for Codepage 1258
+//!
+//! Encoding is performed with
+//! [`encoding_rs`](https://crates.io/crates/encoding_rs), which does not
+//! support insertion of U+FFFD � REPLACEMENT CHARACTER at decoding. Decoding is
+//! performed with [`mail-parser`](https://crates.io/crates/mail-parser), which
+//! does not allow encoding.
+//!
+//! Codepage 1258 only has a few precomposed Vietnamese letters, and writes the
+//! others as a base letter followed by a combining tone mark: `'ễ'` is `'ê'`
+//! followed by U+0303 COMBINING TILDE. Encoding therefore decomposes the
+//! characters that are not in the code page into a precomposed base letter and
+//! tone marks, and decoding recomposes them (NFC).
+//!
+//! The undefined bytes are decoded as U+FFFD � REPLACEMENT CHARACTER by
+//! default, or as another character configured with
+//! [`CP1258Engine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+use encoding_rs::*;
+use mail_parser::*;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::decompose_canonical;
+
+pub struct CP1258Engine
+{
+    /// The character to decode undefined bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`CP1258Engine`].
+pub struct CP1258EngineBuilder
+{
+    engine: CP1258Engine
+}
+
+impl CP1258Engine
+{
+    /// The default engine, decoding undefined bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        CP1258Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> CP1258EngineBuilder
+    {
+        CP1258EngineBuilder { engine: CP1258Engine::new() }
+    }
+
+    /// Decode, with undefined bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let decoded = decoders::charsets::single_byte::decoder_cp1258(bytes)
+            .chars()
+            .map(|c|
+                if c == char::REPLACEMENT_CHARACTER { replacement }
+                else { Some(c) })
+            .collect::<Option<String>>()?;
+        Some(decoded.nfc().collect())
+    }
+}
+
+impl Default for CP1258Engine
+{
+    fn default() -> Self { CP1258Engine::new() }
+}
+
+impl CP1258EngineBuilder
+{
+    /// Set the character to decode undefined bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> CP1258Engine { self.engine }
+}
+
+/// The combining tone marks of Codepage 1258.
+const TONE_MARKS: [char; 5] = ['\u{300}', '\u{301}', '\u{303}', '\u{309}',
+    '\u{323}'];
+
+/// Encode a single character, without decomposition.
+fn encode_char(c: char) -> Option<Vec<u8>>
+{
+    let mut buf = [0u8; 4];
+    let (output, _, error) = WINDOWS_1258.encode(c.encode_utf8(&mut buf));
+    if error
+    {
+        None
+    }
+    else
+    {
+        Some(output.into_owned())
+    }
+}
+
+impl Engine for CP1258Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("Codepage 1258", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            if let Some(bytes) = encode_char(c)
+            {
+                encoded.extend(bytes);
+                continue;
+            }
+            // Base letter with its other diacritics, then tone marks
+            let mut decomposition = Vec::new();
+            decompose_canonical(c, |d| decomposition.push(d));
+            let (tones, base): (Vec<char>, Vec<char>) = decomposition.iter()
+                .partition(|d| TONE_MARKS.contains(d));
+            if tones.is_empty()
+            {
+                return None;
+            }
+            let base: String = base.into_iter().nfc().collect();
+            let mut chars = base.chars();
+            let (Some(base), None) = (chars.next(), chars.next())
+            else
+            {
+                return None;
+            };
+            encoded.extend(encode_char(base)?);
+            for tone in tones
+            {
+                encoded.extend(encode_char(tone)?);
+            }
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        CP1258Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        CP1258Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = CP1258Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("ư").unwrap();
+        assert_eq!(encoded, &[0xfd]);
+
+        let encoded = engine.encode("Nguyễn").unwrap();
+        assert_eq!(encoded, &[0x4e, 0x67, 0x75, 0x79, 0xea, 0xde, 0x6e]);
+
+        let encoded = engine.encode("Ặ").unwrap();
+        assert_eq!(encoded, &[0xc3, 0xf2]);
+
+        assert!(engine.encode("😀").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = CP1258Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(&[0x56, 0x69, 0xea, 0xf2, 0x74]);
+        assert_eq!(decoded, "Việt");
+
+        let decoded = engine.decode(&[0x81]);
+        assert_eq!(decoded, "�");
+
+        let engine = CP1258Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "Codepage 1258 (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x81, 0xf2]);
+        assert_eq!(decoded, "A?\u{323}");
+
+        assert!(engine.try_decode(&[0x41, 0x81, 0xf2]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = CP1258Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,177 @@
+//! Deencoding engine for Codepage 874
+//!
+//! Codepage 874 is a superset of TIS-620 (and ISO 8859-11), with a few
+//! punctuation marks and the euro sign in `0x80` to `0x9F`, which the TIS-620
+//! decoder of [`mail-parser`](https://crates.io/crates/mail-parser) does not
+//! know about.
+//!
+//! Encoding and decoding are therefore both performed with
+//! [`encoding_rs`](https://crates.io/crates/encoding_rs). Since it decodes
+//! some undefined bytes as the matching C1 control code, those are replaced
+//! with U+FFFD � REPLACEMENT CHARACTER, like the other code page engines do,
+//! or with another character configured with [`CP874Engine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+use encoding_rs::*;
+
+pub struct CP874Engine
+{
+    /// The character to decode undefined bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`CP874Engine`].
+pub struct CP874EngineBuilder
+{
+    engine: CP874Engine
+}
+
+impl CP874Engine
+{
+    /// The default engine, decoding undefined bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        CP874Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> CP874EngineBuilder
+    {
+        CP874EngineBuilder { engine: CP874Engine::new() }
+    }
+
+    /// Decode, with undefined bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        WINDOWS_874.decode_without_bom_handling(bytes).0 // Cow<'_, str>
+            .chars()
+            .map(|c|
+                if c == char::REPLACEMENT_CHARACTER
+                    || ('\u{80}'..='\u{9f}').contains(&c)
+                {
+                    replacement
+                }
+                else
+                {
+                    Some(c)
+                })
+            .collect()
+    }
+}
+
+impl Default for CP874Engine
+{
+    fn default() -> Self { CP874Engine::new() }
+}
+
+impl CP874EngineBuilder
+{
+    /// Set the character to decode undefined bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> CP874Engine { self.engine }
+}
+
+impl Engine for CP874Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("Codepage 874", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let (output, _, error) = WINDOWS_874.encode(string);
+        if error
+        {
+            None
+        }
+        else
+        {
+            Some(output.into_owned())
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        CP874Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        CP874Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = CP874Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("ก").unwrap();
+        assert_eq!(encoded, &[0xa1]);
+
+        let encoded = engine.encode("€").unwrap();
+        assert_eq!(encoded, &[0x80]);
+
+        assert!(engine.encode("😀").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = CP874Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "\u{e48}");
+
+        let decoded = engine.decode(&[0x81]);
+        assert_eq!(decoded, "�");
+
+        let decoded = engine.decode(&[0xdb]);
+        assert_eq!(decoded, "�");
+
+        let engine = CP874Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "Codepage 874 (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x81, 0xdb]);
+        assert_eq!(decoded, "A??");
+
+        assert!(engine.try_decode(&[0x41, 0x81, 0x42]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = CP874Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -7,6 +7,11 @@
 //!
 //! It is to note that we intentionally only want to end each path on a decoding
 //! step, which means the depth of the tree will always be an even number.
+//!
+//! In strict mode, decoding steps that would need replacement characters (see
+//! [`Engine::try_decode()`]) are pruned, along with the encoding steps left
+//! without any decoding step, and the number of pruned decoding steps is
+//! recorded.
 
 use crate::engine::*;
 
@@ -48,7 +53,11 @@ pub struct DeencodeTree
     /// The input string.
     pub input: String,
     /// The underlying encoding steps.
-    pub encoders: Vec<EncodeNode>
+    pub encoders: Vec<EncodeNode>,
+    /// The number of decoding steps pruned in strict mode, or `None` outside
+    /// of it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pruned: Option<usize>
 }
 
 impl EncodeNode
@@ -73,14 +82,51 @@ impl EncodeNode
     pub fn make_nodes(input: &str, engines: &[&dyn Engine],
         depth: usize)
         -> Vec<EncodeNode>
+    {
+        EncodeNode::make_nodes_with(input, engines, depth, None)
+    }
+
+    /// <div class="warning">
+    ///
+    /// You probably want [`DeencodeTree::deencode_strict()`], which calls this
+    /// function in a recursive fashion.
+    ///
+    /// </div>
+    ///
+    /// Like [`EncodeNode::make_nodes()`], but recursively calling
+    /// [`DecodeNode::make_nodes_strict()`], and dropping the encodings whose
+    /// decodings were all pruned.
+    ///
+    /// Also gives the number of pruned decodings.
+    pub fn make_nodes_strict(input: &str, engines: &[&dyn Engine],
+        depth: usize)
+        -> (Vec<EncodeNode>, usize)
+    {
+        let mut pruned = 0;
+        let nodes = EncodeNode::make_nodes_with(input, engines, depth,
+            Some(&mut pruned));
+        (nodes, pruned)
+    }
+
+    /// Implementation of [`EncodeNode::make_nodes()`], and of
+    /// [`EncodeNode::make_nodes_strict()`] when there is a count of pruned
+    /// decodings.
+    fn make_nodes_with(input: &str, engines: &[&dyn Engine],
+        depth: usize, mut pruned: Option<&mut usize>)
+        -> Vec<EncodeNode>
     {
         let mut results = Vec::<EncodeNode>::new();
         for &engine in engines.iter()
         {
             if let Some(output) = engine.encode(input)
             {
-                let decoders =
-                    DecodeNode::make_nodes(&output, engines, depth - 1);
+                let strict = pruned.is_some();
+                let decoders = DecodeNode::make_nodes_with(&output, engines,
+                    depth - 1, pruned.as_deref_mut());
+                if strict && decoders.is_empty()
+                {
+                    continue;
+                }
                 results.push(EncodeNode {
                     name: engine.get_name(), output, decoders
                 });
@@ -173,25 +219,70 @@ impl DecodeNode
     pub fn make_nodes(input: &[u8], engines: &[&dyn Engine],
         depth: usize)
         -> Vec<DecodeNode>
+    {
+        DecodeNode::make_nodes_with(input, engines, depth, None)
+    }
+
+    /// <div class="warning">
+    ///
+    /// You probably want [`DeencodeTree::deencode_strict()`], which calls this
+    /// function in a recursive fashion.
+    ///
+    /// </div>
+    ///
+    /// Like [`DecodeNode::make_nodes()`], but using [`Engine::try_decode()`]
+    /// instead of [`Engine::decode()`], skipping the engines that fail to
+    /// decode, and recursively calling [`EncodeNode::make_nodes_strict()`].
+    ///
+    /// Also gives the number of pruned decodings.
+    pub fn make_nodes_strict(input: &[u8], engines: &[&dyn Engine],
+        depth: usize)
+        -> (Vec<DecodeNode>, usize)
+    {
+        let mut pruned = 0;
+        let nodes = DecodeNode::make_nodes_with(input, engines, depth,
+            Some(&mut pruned));
+        (nodes, pruned)
+    }
+
+    /// Implementation of [`DecodeNode::make_nodes()`], and of
+    /// [`DecodeNode::make_nodes_strict()`] when there is a count of pruned
+    /// decodings.
+    fn make_nodes_with(input: &[u8], engines: &[&dyn Engine],
+        depth: usize, mut pruned: Option<&mut usize>)
+        -> Vec<DecodeNode>
     {
         engines.iter()
-            .map(|e|
+            .filter_map(|e|
                 {
-                    let output = e.decode(input);
+                    let output = match pruned.as_deref_mut()
+                    {
+                        Some(pruned) =>
+                        {
+                            let output = e.try_decode(input);
+                            if output.is_none()
+                            {
+                                *pruned += 1;
+                            }
+                            output?
+                        },
+                        None => e.decode(input)
+                    };
                     let encoders = if 0 < depth
                     {
-                        EncodeNode::make_nodes(&output, engines, depth)
+                        EncodeNode::make_nodes_with(&output, engines, depth,
+                            pruned.as_deref_mut())
                     }
                     else
                     {
                         Vec::new()
                     };
-                    DecodeNode {
+                    Some(DecodeNode {
                         name: e.get_name(),
                         output,
                         encoders,
                         is_leaf: depth == 0
-                    }
+                    })
                 })
         .collect()
     }
@@ -219,7 +310,7 @@ impl DecodeNode
                 continue;
             }
             encoder.deduplicate(known_strings, known_bytes);
-            if encoder.decoders.len() == 0
+            if encoder.decoders.is_empty()
             {
                 todelete.push(i);
             }
@@ -272,7 +363,25 @@ impl DeencodeTree
     {
         DeencodeTree {
             input: input.to_owned(),
-            encoders: EncodeNode::make_nodes(input, engines, depth)
+            encoders: EncodeNode::make_nodes(input, engines, depth),
+            pruned: None
+        }
+    }
+
+    /// Like [`DeencodeTree::deencode()`], but pruning the decodings that would
+    /// need replacement characters (see [`Engine::try_decode()`]), to only keep
+    /// the branches that decode cleanly.
+    ///
+    /// The number of pruned decodings is recorded in `pruned`.
+    pub fn deencode_strict(input: &str, engines: &[&dyn Engine], depth: usize)
+        -> DeencodeTree
+    {
+        let (encoders, pruned) =
+            EncodeNode::make_nodes_strict(input, engines, depth);
+        DeencodeTree {
+            input: input.to_owned(),
+            encoders,
+            pruned: Some(pruned)
         }
     }
 
@@ -328,6 +437,11 @@ impl DeencodeTree
             write!(f, "\n└╴")?;
             encoder.box_drawings("\n  ", f)?;
         }
+        if let Some(pruned) = self.pruned
+        {
+            write!(f, "\n(pruned {} decoding(s) needing replacement \
+                characters)", pruned)?;
+        }
         Ok(())
     }
 }
@@ -340,3 +454,35 @@ impl fmt::Display for DeencodeTree
         self.box_drawings(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::latin1engine::Latin1Engine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn deencode_strict()
+    {
+        let utf8 = Utf8Engine::new();
+        let latin1 = Latin1Engine::new();
+        let engines: Vec<&dyn Engine> = vec![&utf8, &latin1];
+
+        let tree = DeencodeTree::deencode("Clément", &engines, 1);
+        assert_eq!(tree.pruned, None);
+        assert_eq!(tree.encoders[1].decoders[0].output, "Cl�ment");
+
+        let tree = DeencodeTree::deencode_strict("Clément", &engines, 1);
+        // Latin-1 decoded as UTF-8
+        assert_eq!(tree.pruned, Some(1));
+        assert_eq!(tree.encoders[1].decoders.len(), 1);
+        assert_eq!(tree.encoders[1].decoders[0].output, "Clément");
+
+        let tree = DeencodeTree::deencode_strict("ŝ", &engines, 1);
+        // Latin-1 cannot encode it, and its UTF-8 has an undefined byte
+        assert_eq!(tree.pruned, Some(1));
+        assert_eq!(tree.encoders.len(), 1);
+        assert_eq!(tree.encoders[0].decoders[0].output, "ŝ");
+    }
+}
@@ -1,3 +1,5 @@
+pub mod conformance;
+
 /// A deencoding engine.
 pub trait Engine
 {
@@ -14,4 +16,36 @@ pub trait Engine
     /// Failure is not accepted: any encoding charset must be covered by Unicode
     /// and therefore Rust strings.
     fn decode(&self, bytes: &[u8]) -> String;
+
+    /// Decode through the engine, without replacing invalid sequences.
+    ///
+    /// Failure is allowed where [`Engine::decode()`] would have to insert
+    /// U+FFFD � REPLACEMENT CHARACTER, or another replacement. The default
+    /// implementation fails on any U+FFFD in the output of `decode()`, which is
+    /// exact for engines that cannot decode a genuine one.
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let decoded = self.decode(bytes);
+        if decoded.contains(char::REPLACEMENT_CHARACTER)
+        {
+            None
+        }
+        else
+        {
+            Some(decoded)
+        }
+    }
+}
+
+/// The name of a configurable engine, followed by its non-default options.
+pub(crate) fn name_with_options(name: &str, options: &[String]) -> String
+{
+    if options.is_empty()
+    {
+        name.to_string()
+    }
+    else
+    {
+        format!("{} ({})", name, options.join(", "))
+    }
 }
@@ -0,0 +1,150 @@
+//! Conformance checks for engines
+//!
+//! The [`Engine`] trait comes with a contract that the compiler cannot check:
+//! decoding must not fail, whatever the bytes, while encoding and strict
+//! decoding may. These checks can be used in the tests of any engine, including
+//! the ones of this crate, and panic with a message naming the engine and the
+//! faulty input:
+//!
+//! ```rust
+//! use deencode::engine::conformance;
+//! use deencode::*;
+//!
+//! conformance::assert_deterministic_name(&LATIN1);
+//! conformance::assert_decode_never_panics(&LATIN1);
+//! conformance::assert_try_decode_agrees(&LATIN1);
+//! conformance::assert_round_trips(&LATIN1, conformance::SAMPLES);
+//! conformance::assert_ascii_transparent(&LATIN1);
+//! ```
+//!
+//! Only the first three checks apply to every engine: lossy engines, such as
+//! transliteration, do not round trip, and many engines escape or move some
+//! ASCII characters.
+
+use crate::engine::Engine;
+
+use std::panic::{self, AssertUnwindSafe};
+
+/// Sample strings in many scripts, to check round trips with.
+pub const SAMPLES: &[&str] = &[
+    "",
+    "Hello, world!",
+    "\t\r\n\0\u{7f}",
+    "\\\"'&<>{}[]+-~@$#%",
+    "Clément",
+    "Ærøskøbing, Straße",
+    "łódź, Žluťoučký kůň",
+    "Ελληνικά, Кириллица",
+    "İstanbul, Diyarbakır",
+    "עברית, العربية",
+    "Tiếng Việt",
+    "ภาษาไทย",
+    "日本語, 한국어",
+    "€£¥©®™…“”",
+    "😀🇫🇷",
+];
+
+/// The number of pseudo-random byte strings decoded.
+const RANDOM_COUNT: usize = 1000;
+
+/// The maximum length of the pseudo-random byte strings decoded.
+const RANDOM_LENGTH: usize = 32;
+
+/// Check that `get_name()` is not empty, and always gives the same name.
+pub fn assert_deterministic_name(engine: &dyn Engine)
+{
+    let name = engine.get_name();
+    assert!(!name.is_empty(), "the name of the engine is empty");
+    assert_eq!(engine.get_name(), name, "the name of the engine changed");
+}
+
+/// Every byte string used to check decoding: the empty string, every byte and
+/// pair of bytes, and pseudo-random byte strings.
+fn arbitrary_bytes() -> impl Iterator<Item = Vec<u8>>
+{
+    let pairs = (0..=0xff).flat_map(|first|
+        std::iter::once(vec![first])
+            .chain((0..=0xff).map(move |second| vec![first, second])));
+
+    // xorshift32, deterministic to make failures reproducible
+    let mut state: u32 = 0x2545_f491;
+    let mut next = move ||
+    {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+    let random = (0..RANDOM_COUNT).map(move |_|
+    {
+        let length = next() as usize % (RANDOM_LENGTH + 1);
+        (0..length).map(|_| next() as u8).collect()
+    });
+
+    std::iter::once(Vec::new()).chain(pairs).chain(random)
+}
+
+/// Check that decoding does not panic: on the empty string, on every byte and
+/// pair of bytes, and on pseudo-random byte strings.
+pub fn assert_decode_never_panics(engine: &dyn Engine)
+{
+    for bytes in arbitrary_bytes()
+    {
+        let result = panic::catch_unwind(AssertUnwindSafe(||
+            engine.decode(&bytes)));
+        assert!(result.is_ok(), "{}: decoding {:02X?} panicked",
+            engine.get_name(), bytes);
+    }
+}
+
+/// Check that strict decoding, when it succeeds, gives the same string as
+/// decoding, on the same byte strings as
+/// [`assert_decode_never_panics()`].
+pub fn assert_try_decode_agrees(engine: &dyn Engine)
+{
+    for bytes in arbitrary_bytes()
+    {
+        if let Some(decoded) = engine.try_decode(&bytes)
+        {
+            assert_eq!(decoded, engine.decode(&bytes),
+                "{}: strict decoding of {:02X?} disagrees",
+                engine.get_name(), bytes);
+        }
+    }
+}
+
+/// Check that every sample, and every character of the samples, that the
+/// engine can encode, decodes back to itself.
+pub fn assert_round_trips(engine: &dyn Engine, samples: &[&str])
+{
+    let characters = samples.iter()
+        .flat_map(|s| s.chars())
+        .map(String::from);
+    let strings = samples.iter()
+        .map(|s| s.to_string())
+        .chain(characters);
+
+    for string in strings
+    {
+        if let Some(encoded) = engine.encode(&string)
+        {
+            assert_eq!(engine.decode(&encoded), string,
+                "{}: {:?} encoded as {:02X?} does not decode back",
+                engine.get_name(), string, encoded);
+        }
+    }
+}
+
+/// Check that every ASCII character encodes as its own byte, and that every
+/// byte below `0x80` decodes as its own character.
+pub fn assert_ascii_transparent(engine: &dyn Engine)
+{
+    for byte in 0..0x80
+    {
+        let string = (byte as char).to_string();
+        assert_eq!(engine.encode(&string), Some(vec![byte]),
+            "{}: {:?} is not encoded as itself", engine.get_name(), string);
+        assert_eq!(engine.decode(&[byte]), string,
+            "{}: {:02X} is not decoded as itself", engine.get_name(), byte);
+    }
+}
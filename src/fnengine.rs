@@ -0,0 +1,114 @@
+//! Deencoding engine built from closures
+//!
+//! Writing a type and implementing [`Engine`] is overkill to try out an idea,
+//! or for a one-off transformation in a test. This engine is made of a name
+//! and two closures, which are called for encoding and decoding.
+
+use crate::engine::Engine;
+
+pub struct FnEngine<Enc, Dec>
+    where Enc: Fn(&str) -> Option<Vec<u8>>, Dec: Fn(&[u8]) -> String
+{
+    /// The name of the engine.
+    pub name: String,
+    /// The closure called for encoding.
+    pub encoder: Enc,
+    /// The closure called for decoding.
+    pub decoder: Dec
+}
+
+impl<Enc, Dec> FnEngine<Enc, Dec>
+    where Enc: Fn(&str) -> Option<Vec<u8>>, Dec: Fn(&[u8]) -> String
+{
+    /// Build an engine from a name and closures.
+    pub fn new(name: &str, encoder: Enc, decoder: Dec) -> FnEngine<Enc, Dec>
+    {
+        FnEngine { name: name.to_string(), encoder, decoder }
+    }
+}
+
+impl<Enc, Dec> Engine for FnEngine<Enc, Dec>
+    where Enc: Fn(&str) -> Option<Vec<u8>>, Dec: Fn(&[u8]) -> String
+{
+    fn get_name(&self) -> String { self.name.clone() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        (self.encoder)(string)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        (self.decoder)(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::chainengine::ChainEngine;
+    use crate::engine::conformance;
+    use crate::utf8engine::Utf8Engine;
+
+    /// Uppercase hexadecimal, as a prototype.
+    fn hex() -> impl Engine
+    {
+        FnEngine::new("hexadecimal",
+            |string| Some(string.chars()
+                .map(|c| u8::try_from(c).ok().map(|b| format!("{:02X}", b)))
+                .collect::<Option<String>>()?
+                .into_bytes()),
+            |bytes| bytes.chunks(2)
+                .map(|pair| std::str::from_utf8(pair).ok()
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .map_or(char::REPLACEMENT_CHARACTER, |b| b as char))
+                .collect())
+    }
+
+    #[test]
+    fn encode() {
+        let engine = hex();
+
+        assert_eq!(engine.get_name(), "hexadecimal");
+
+        let encoded = engine.encode("Hi").unwrap();
+        assert_eq!(encoded, b"4869");
+
+        assert!(engine.encode("€").is_none());
+
+        let utf8 = Utf8Engine::new();
+        let hex = hex();
+        let engine = ChainEngine { engines: vec![&utf8, &hex] };
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"C3A9");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = hex();
+
+        let decoded = engine.decode(b"776F726C6421");
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"E9Z");
+        assert_eq!(decoded, "é�");
+
+        let tree = crate::deencode("é", &[&engine, &Utf8Engine::new()], 1);
+        assert_eq!(tree.encoders[0].name, "hexadecimal");
+        assert_eq!(tree.encoders[0].decoders[1].output, "E9");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = hex();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -0,0 +1,467 @@
+//! Deencoding engine for the GSM 03.38 SMS alphabet
+//!
+//! SMS are usually written in the GSM 7-bit default alphabet (3GPP TS 23.038),
+//! which covers ASCII letters and digits, but only a handful of accented
+//! letters and Greek capitals, and moves some ASCII symbols around (`'@'` is
+//! `0x00`, `'$'` is `0x02`, `'_'` is `0x11`). Less common characters such as
+//! `'€'`, `'['` or `'|'` are in an extension table, reached by prefixing them
+//! with the escape code `0x1B`.
+//!
+//! National language shift tables replace the default alphabet (locking shift)
+//! and/or the extension table (single shift) to cover other languages. The
+//! Turkish locking and single shift tables, and the Spanish single shift table,
+//! are supported; other languages are not.
+//!
+//! The septets are either stored one per byte, or packed 8 into 7 bytes, least
+//! significant bits first, as they are sent over the air. When packing leaves
+//! 7 spare bits at the end, they are filled with a carriage return rather than
+//! `'@'`, as recommended for USSD, and such a trailing carriage return is
+//! removed at decoding. A message really ending with a carriage return on an
+//! octet boundary gets another one, as 3GPP TS 23.038 §6.1.2.3.1 requires,
+//! which is removed at decoding too.
+//!
+//! Encoding fails on characters outside the tables. Decoding gives U+FFFD �
+//! REPLACEMENT CHARACTER, or another character configured with
+//! [`GsmEngine::builder()`], for bytes outside of 7 bits when unpacked, and for
+//! a dangling escape code. Escaped codes that are not in the extension table
+//! decode as their character in the default alphabet, as 3GPP TS 23.038
+//! recommends.
+
+use crate::engine::{name_with_options, Engine};
+
+/// The escape code to the extension table.
+const ESC: u8 = 0x1b;
+/// The carriage return, used to pad the last septet.
+const CR: u8 = 0x0d;
+
+/// The GSM 7-bit default alphabet. The escape code is mapped to U+001B.
+const DEFAULT: [char; 128] = [
+    '@', '£', '$', '¥', 'è', 'é', 'ù', 'ì', 'ò', 'Ç', '\n', 'Ø', 'ø', '\r', 'Å',
+    'å', 'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', '\u{1b}', 'Æ',
+    'æ', 'ß', 'É', ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+',
+    ',', '-', '.', '/', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':',
+    ';', '<', '=', '>', '?', '¡', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X',
+    'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§', '¿', 'a', 'b', 'c', 'd', 'e', 'f', 'g',
+    'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v',
+    'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à',
+];
+
+/// The Turkish locking shift table. The escape code is mapped to U+001B.
+const TURKISH: [char; 128] = [
+    '@', '£', '$', '¥', '€', 'é', 'ù', 'ı', 'ò', 'Ç', '\n', 'Ğ', 'ğ', '\r', 'Å',
+    'å', 'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', '\u{1b}', 'Ş',
+    'ş', 'ß', 'É', ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+',
+    ',', '-', '.', '/', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':',
+    ';', '<', '=', '>', '?', 'İ', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X',
+    'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§', 'ç', 'a', 'b', 'c', 'd', 'e', 'f', 'g',
+    'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v',
+    'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à',
+];
+
+/// The default extension table.
+const DEFAULT_EXTENSION: &[(u8, char)] = &[
+    (0x0a, '\u{0c}'), (0x14, '^'), (0x28, '{'), (0x29, '}'), (0x2f, '\\'),
+    (0x3c, '['), (0x3d, '~'), (0x3e, ']'), (0x40, '|'), (0x65, '€'),
+];
+
+/// The Turkish single shift table.
+const TURKISH_EXTENSION: &[(u8, char)] = &[
+    (0x0a, '\u{0c}'), (0x14, '^'), (0x28, '{'), (0x29, '}'), (0x2f, '\\'),
+    (0x3c, '['), (0x3d, '~'), (0x3e, ']'), (0x40, '|'), (0x47, 'Ğ'),
+    (0x49, 'İ'), (0x53, 'Ş'), (0x63, 'ç'), (0x65, '€'), (0x67, 'ğ'),
+    (0x69, 'ı'), (0x73, 'ş'),
+];
+
+/// The Spanish single shift table.
+const SPANISH_EXTENSION: &[(u8, char)] = &[
+    (0x09, 'ç'), (0x0a, '\u{0c}'), (0x14, '^'), (0x28, '{'), (0x29, '}'),
+    (0x2f, '\\'), (0x3c, '['), (0x3d, '~'), (0x3e, ']'), (0x40, '|'),
+    (0x41, 'Á'), (0x49, 'Í'), (0x4f, 'Ó'), (0x55, 'Ú'), (0x61, 'á'),
+    (0x65, '€'), (0x69, 'í'), (0x6f, 'ó'), (0x75, 'ú'),
+];
+
+/// The shift tables in use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GsmLanguage
+{
+    /// The default alphabet and extension table.
+    Default,
+    /// The Turkish locking and single shift tables.
+    Turkish,
+    /// The default alphabet and the Spanish single shift table.
+    Spanish
+}
+
+impl GsmLanguage
+{
+    /// The locking shift table.
+    fn alphabet(&self) -> &'static [char; 128]
+    {
+        match self
+        {
+            GsmLanguage::Turkish => &TURKISH,
+            _ => &DEFAULT
+        }
+    }
+
+    /// The single shift table.
+    fn extension(&self) -> &'static [(u8, char)]
+    {
+        match self
+        {
+            GsmLanguage::Default => DEFAULT_EXTENSION,
+            GsmLanguage::Turkish => TURKISH_EXTENSION,
+            GsmLanguage::Spanish => SPANISH_EXTENSION
+        }
+    }
+}
+
+pub struct GsmEngine
+{
+    /// The shift tables in use.
+    pub language: GsmLanguage,
+    /// Whether septets are packed 8 into 7 bytes.
+    pub packed: bool,
+    /// The character to decode invalid septets as.
+    pub replacement: char
+}
+
+/// Builder for a [`GsmEngine`].
+pub struct GsmEngineBuilder
+{
+    engine: GsmEngine
+}
+
+/// Pack septets 8 into 7 bytes, least significant bits first.
+fn pack(septets: &[u8]) -> Vec<u8>
+{
+    let mut packed = Vec::new();
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    let mut septets = septets.to_vec();
+    if septets.len().is_multiple_of(8) && septets.last() == Some(&CR)
+    {
+        // A trailing carriage return would be taken for padding
+        septets.push(CR);
+    }
+    for septet in septets
+    {
+        buffer |= (septet as u16) << bits;
+        bits += 7;
+        if 8 <= bits
+        {
+            packed.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    }
+    if bits == 1
+    {
+        // 7 spare bits, which would decode as '@'
+        buffer |= (CR as u16) << 1;
+        bits += 7;
+    }
+    if 0 < bits
+    {
+        packed.push(buffer as u8);
+    }
+    packed
+}
+
+/// Unpack septets packed 8 into 7 bytes, least significant bits first.
+fn unpack(bytes: &[u8]) -> Vec<u8>
+{
+    let mut septets = Vec::new();
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for &byte in bytes
+    {
+        buffer |= (byte as u16) << bits;
+        bits += 8;
+        while 7 <= bits
+        {
+            septets.push((buffer & 0x7f) as u8);
+            buffer >>= 7;
+            bits -= 7;
+        }
+    }
+    if bytes.len().is_multiple_of(7) && septets.last() == Some(&CR)
+    {
+        // Padding of the last septet
+        septets.pop();
+    }
+    else if septets.len() % 8 == 1 && septets.ends_with(&[CR, CR])
+    {
+        // Carriage return added after a trailing one on an octet boundary
+        septets.pop();
+    }
+    septets
+}
+
+impl GsmEngine
+{
+    /// The default engine: default alphabet, one septet per byte, and U+FFFD.
+    pub const fn new() -> Self
+    {
+        GsmEngine {
+            language: GsmLanguage::Default,
+            packed: false,
+            replacement: char::REPLACEMENT_CHARACTER
+        }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> GsmEngineBuilder
+    {
+        GsmEngineBuilder { engine: GsmEngine::new() }
+    }
+
+    /// Decode, with invalid septets and a dangling escape code as
+    /// `replacement`, or failing without one.
+    fn decode_with(&self, bytes: &[u8], replacement: Option<char>)
+        -> Option<String>
+    {
+        let alphabet = self.language.alphabet();
+        let extension = self.language.extension();
+        let septets = if self.packed { unpack(bytes) } else { bytes.to_vec() };
+        let mut decoded = String::new();
+
+        let mut i = 0;
+        while i < septets.len()
+        {
+            let septet = septets[i];
+            i += 1;
+            if 0x80 <= septet
+            {
+                decoded.push(replacement?);
+            }
+            else if septet != ESC
+            {
+                decoded.push(alphabet[septet as usize]);
+            }
+            else if let Some(&code) = septets.get(i).filter(|&&s| s < 0x80)
+            {
+                i += 1;
+                match extension.iter().find(|(e, _)| *e == code)
+                {
+                    Some((_, c)) => decoded.push(*c),
+                    None if code == ESC => decoded.push(' '),
+                    None => decoded.push(alphabet[code as usize])
+                }
+            }
+            else
+            {
+                // Cannot read the escaped code
+                decoded.push(replacement?);
+            }
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Default for GsmEngine
+{
+    fn default() -> Self { GsmEngine::new() }
+}
+
+impl GsmEngineBuilder
+{
+    /// Set the shift tables in use.
+    pub const fn language(mut self, language: GsmLanguage) -> Self
+    {
+        self.engine.language = language;
+        self
+    }
+
+    /// Set whether septets are packed 8 into 7 bytes.
+    pub const fn packed(mut self, packed: bool) -> Self
+    {
+        self.engine.packed = packed;
+        self
+    }
+
+    /// Set the character to decode invalid septets as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> GsmEngine { self.engine }
+}
+
+impl Engine for GsmEngine
+{
+    fn get_name(&self) -> String
+    {
+        let mut name = "GSM 03.38".to_string();
+        if self.language != GsmLanguage::Default
+        {
+            name += &format!(" ({:?})", self.language);
+        }
+        if self.packed
+        {
+            name += " packed";
+        }
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options(&name, &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let alphabet = self.language.alphabet();
+        let extension = self.language.extension();
+        let mut septets = Vec::new();
+
+        for c in string.chars()
+        {
+            if c == '\u{1b}'
+            {
+                return None;
+            }
+            if let Some(code) = alphabet.iter().position(|&a| a == c)
+            {
+                septets.push(code as u8);
+            }
+            else
+            {
+                let (code, _) = extension.iter().find(|(_, e)| *e == c)?;
+                septets.push(ESC);
+                septets.push(*code);
+            }
+        }
+
+        if self.packed
+        {
+            Some(pack(&septets))
+        }
+        else
+        {
+            Some(septets)
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        self.decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        self.decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = GsmEngine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("é@$").unwrap();
+        assert_eq!(encoded, &[0x05, 0x00, 0x02]);
+
+        let encoded = engine.encode("€[").unwrap();
+        assert_eq!(encoded, &[0x1b, 0x65, 0x1b, 0x3c]);
+
+        assert!(engine.encode("ç").is_none());
+        assert!(engine.encode("😀").is_none());
+
+        let engine = GsmEngine::builder()
+            .language(GsmLanguage::Turkish)
+            .build();
+
+        let encoded = engine.encode("ış€{").unwrap();
+        assert_eq!(encoded, &[0x07, 0x1d, 0x04, 0x1b, 0x28]);
+
+        let engine = GsmEngine::builder().packed(true).build();
+
+        let encoded = engine.encode("hellohello").unwrap();
+        // 3GPP TS 23.038 §6.1.2.1.1
+        assert_eq!(encoded,
+            &[0xe8, 0x32, 0x9b, 0xfd, 0x46, 0x97, 0xd9, 0xec, 0x37]);
+
+        let encoded = engine.encode("1234567").unwrap();
+        // The 7 spare bits are filled with a carriage return
+        assert_eq!(encoded, &[0x31, 0xd9, 0x8c, 0x56, 0xb3, 0xdd, 0x1a]);
+
+        let encoded = engine.encode("1234567\r").unwrap();
+        // The trailing carriage return is followed by another one
+        assert_eq!(encoded,
+            &[0x31, 0xd9, 0x8c, 0x56, 0xb3, 0xdd, 0x1a, 0x0d]);
+        assert_eq!(engine.decode(&encoded), "1234567\r");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = GsmEngine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0x04, 0x11, 0x1b, 0x40]);
+        assert_eq!(decoded, "è_|");
+
+        let decoded = engine.decode(&[0x1b, 0x41, 0x1b]);
+        assert_eq!(decoded, "A�");
+
+        let decoded = engine.decode(&[0xe9]);
+        assert_eq!(decoded, "�");
+
+        let engine = GsmEngine::builder()
+            .language(GsmLanguage::Spanish)
+            .build();
+
+        let decoded = engine.decode(&[0x1b, 0x69, 0x1b, 0x09]);
+        assert_eq!(decoded, "íç");
+
+        let engine = GsmEngine::builder().packed(true).build();
+
+        let decoded = engine.decode(
+            &[0xe8, 0x32, 0x9b, 0xfd, 0x46, 0x97, 0xd9, 0xec, 0x37]);
+        assert_eq!(decoded, "hellohello");
+
+        let decoded =
+            engine.decode(&[0x31, 0xd9, 0x8c, 0x56, 0xb3, 0xdd, 0x1a]);
+        assert_eq!(decoded, "1234567");
+
+        let engine = GsmEngine::builder()
+            .language(GsmLanguage::Turkish)
+            .replacement('?')
+            .build();
+        assert_eq!(engine.get_name(), "GSM 03.38 (Turkish) (errors as '?')");
+
+        let decoded = engine.decode(&[0x07, 0xe9, 0x1b]);
+        assert_eq!(decoded, "ı??");
+
+        assert!(engine.try_decode(&[0x07, 0x1b]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = GsmEngine::builder().packed(true).build();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
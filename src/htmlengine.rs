@@ -0,0 +1,211 @@
+//! Deencoding engine for HTML/XML character references
+//!
+//! This is not a charset but an escaping scheme: encoding replaces every
+//! non-ASCII scalar, as well as the markup characters `&`, `<`, `>` and `"`,
+//! with a character reference, and outputs the resulting ASCII text. Since `&`
+//! is itself escaped, chaining the engine twice reproduces double escaping:
+//! `"Clément"` becomes `"Cl&eacute;ment"`, then `"Cl&amp;eacute;ment"`.
+//!
+//! Encoding with named references uses the HTML5 entity set of
+//! [`entities`](https://crates.io/crates/entities), falling back to numeric
+//! references for scalars without a name. Decoding is performed with
+//! [`html-escape`](https://crates.io/crates/html-escape), which understands the
+//! full HTML5 named entity set as well as decimal and hexadecimal references.
+//! Bytes are read as UTF-8 before unescaping. Malformed references are kept
+//! as-is, so strict decoding only fails on invalid UTF-8, and accepts a genuine
+//! `&#xFFFD;`.
+
+use crate::engine::Engine;
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use entities::ENTITIES;
+use html_escape::decode_html_entities;
+
+pub struct HtmlEngine
+{
+    /// Whether to prefer named references (`&eacute;`) over numeric ones
+    /// (`&#233;`).
+    pub named: bool
+}
+
+/// The shortest named reference for each scalar that has one, such as
+/// `"&eacute;"` for `'é'` and `"&nbsp;"` (rather than
+/// `"&NonBreakingSpace;"`) for U+00A0.
+fn named_references() -> &'static HashMap<char, &'static str>
+{
+    static NAMES: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+    NAMES.get_or_init(||
+        {
+            let mut names = HashMap::<char, &'static str>::new();
+            for entity in ENTITIES.iter()
+            {
+                // Legacy forms without the semicolon are only for decoding
+                if !entity.entity.ends_with(';')
+                {
+                    continue;
+                }
+                let mut chars = entity.characters.chars();
+                let (Some(c), None) = (chars.next(), chars.next())
+                else
+                {
+                    continue;
+                };
+                names.entry(c)
+                    .and_modify(|name| if entity.entity.len() < name.len()
+                        {
+                            *name = entity.entity;
+                        })
+                    .or_insert(entity.entity);
+            }
+            names
+        })
+}
+
+impl Engine for HtmlEngine
+{
+    fn get_name(&self) -> String
+    {
+        if self.named
+        {
+            "HTML named references".to_string()
+        }
+        else
+        {
+            "HTML numeric references".to_string()
+        }
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = String::new();
+
+        for c in string.chars()
+        {
+            match c
+            {
+                '&' => encoded.push_str("&amp;"),
+                '<' => encoded.push_str("&lt;"),
+                '>' => encoded.push_str("&gt;"),
+                '"' => encoded.push_str("&quot;"),
+                _ if c.is_ascii() => encoded.push(c),
+                _ =>
+                {
+                    match named_references().get(&c)
+                    {
+                        Some(name) if self.named => encoded.push_str(name),
+                        _ => encoded.push_str(&format!("&#{};", c as u32))
+                    }
+                }
+            }
+        }
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        decode_html_entities(&String::from_utf8_lossy(bytes)).into_owned()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let string = std::str::from_utf8(bytes).ok()?;
+        Some(decode_html_entities(string).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let engine = HtmlEngine{ named: true };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"&eacute;");
+
+        let encoded = engine.encode("\u{a0}").unwrap();
+        assert_eq!(encoded, b"&nbsp;");
+
+        let encoded = engine.encode("<&>").unwrap();
+        assert_eq!(encoded, b"&lt;&amp;&gt;");
+
+        let encoded = engine.encode("😀").unwrap();
+        // U+1F600
+        // => 128512
+        assert_eq!(encoded, b"&#128512;");
+
+        let engine = HtmlEngine{ named: false };
+
+        let encoded = engine.encode("é").unwrap();
+        // U+00E9
+        // => 233
+        assert_eq!(encoded, b"&#233;");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = HtmlEngine{ named: true };
+
+        let decoded = engine.decode(b"world&excl;");
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"&egrave;");
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(b"&#x20A4;");
+        assert_eq!(decoded, "₤");
+
+        let decoded = engine.decode(b"Cl&#195;&#169;ment");
+        assert_eq!(decoded, "ClÃ©ment");
+
+        let decoded = engine.decode(b"&#xD800;");
+        assert_eq!(decoded, "&#xD800;");
+
+        assert!(engine.try_decode(&[0xe9]).is_none());
+
+        let decoded = engine.try_decode(b"&#xFFFD;");
+        assert_eq!(decoded.unwrap(), "�");
+    }
+
+    #[test]
+    fn double_escaping()
+    {
+        let engine = HtmlEngine{ named: true };
+
+        let once = Utf8Engine::new().decode(&engine.encode("Clément").unwrap());
+        assert_eq!(once, "Cl&eacute;ment");
+        let twice = engine.encode(&once).unwrap();
+        assert_eq!(twice, b"Cl&amp;eacute;ment");
+        assert_eq!(engine.decode(&twice), "Cl&eacute;ment");
+
+        let engine = HtmlEngine{ named: false };
+
+        let mangled = Latin1Engine::new()
+            .decode(&Utf8Engine::new().encode("Clément").unwrap());
+        let encoded = engine.encode(&mangled).unwrap();
+        assert_eq!(encoded, b"Cl&#195;&#169;ment");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = HtmlEngine{ named: true };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
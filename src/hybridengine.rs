@@ -0,0 +1,111 @@
+//! Deencoding engine for UTF-8 with a single byte fallback
+//!
+//! Many readers decode valid UTF-8 sequences as UTF-8, but fall back to a
+//! legacy code page for the bytes that are not valid UTF-8, instead of
+//! replacing them with U+FFFD � REPLACEMENT CHARACTER: browsers in quirks
+//! mode, "sloppy" decoders such as ftfy's, or PHP applications that try one
+//! then the other. Files that are half UTF-8 and half Latin-1, from appending
+//! to a file with two different programs, read fine that way.
+//!
+//! This engine wraps the fallback engine, which decodes each invalid sequence.
+//! Encoding is plain UTF-8, since such readers write UTF-8.
+
+use crate::engine::Engine;
+
+pub struct HybridEngine<'a, E: Engine + ?Sized>
+{
+    /// The fallback engine, for bytes that are not valid UTF-8.
+    pub engine: &'a E
+}
+
+impl<E: Engine + ?Sized> Engine for HybridEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        format!("UTF-8 with {} fallback", self.engine.get_name())
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        Some(Vec::from(string.as_bytes()))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let mut decoded = String::new();
+
+        for chunk in bytes.utf8_chunks()
+        {
+            decoded.push_str(chunk.valid());
+            decoded.push_str(&self.engine.decode(chunk.invalid()));
+        }
+
+        decoded
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let mut decoded = String::new();
+
+        for chunk in bytes.utf8_chunks()
+        {
+            decoded.push_str(chunk.valid());
+            decoded.push_str(&self.engine.try_decode(chunk.invalid())?);
+        }
+
+        Some(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cp1253engine::CP1253Engine;
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+
+    #[test]
+    fn encode() {
+        let engine = HybridEngine { engine: &Latin1Engine::new() };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xc3, 0xa9]);
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = HybridEngine { engine: &Latin1Engine::new() };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"Cl\xc3\xa9ment, Cl\xe9ment");
+        assert_eq!(decoded, "Clément, Clément");
+
+        let decoded = engine.decode(b"\xe2\x82\x80\xe2\x82");
+        // Truncated sequence
+        assert_eq!(decoded, "₀â‚");
+
+        let engine = HybridEngine { engine: &CP1253Engine::new() };
+
+        let decoded = engine.decode(b"\xe1\xce\xb2");
+        assert_eq!(decoded, "αβ");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = HybridEngine { engine: &Latin1Engine::new() };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,146 @@
+//! Deencoding engine for IDNA labels
+//!
+//! Internationalized domain names are stored as ASCII by converting each label
+//! containing non-ASCII scalars to Punycode with an `xn--` prefix, so
+//! `"clément.fr"` becomes `"xn--clment-cva.fr"`. The labels are separated by
+//! the full stop `.`, as well as its ideographic, fullwidth and halfwidth
+//! variants, which IDNA treats as equivalent. If the string is an email
+//! address, only the domain after the last `@` is converted, and the local
+//! part is written as UTF-8.
+//!
+//! No UTS #46 mapping (case folding, normalisation) is performed, and labels
+//! are not validated, so that mangling from other steps stays visible.
+//!
+//! Bytes are read as UTF-8 before decoding, then only the `xn--` labels are
+//! decoded; those that are not valid Punycode are kept as-is, like browsers
+//! do. Strict decoding therefore only fails on invalid UTF-8.
+
+use crate::engine::Engine;
+use crate::punycodeengine::{punycode_decode, punycode_encode};
+
+const PREFIX: &str = "xn--";
+
+pub struct IdnaEngine {}
+
+/// Split `string` into the local part of an email address, including the `@`,
+/// and the domain. The local part is empty if `string` is not an address.
+fn split_address(string: &str) -> (&str, &str)
+{
+    match string.rfind('@')
+    {
+        Some(i) => string.split_at(i + 1),
+        None => ("", string)
+    }
+}
+
+impl Engine for IdnaEngine
+{
+    fn get_name(&self) -> String { "IDNA".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let (local, domain) = split_address(string);
+        let mut encoded = String::from(local);
+
+        let domain = domain.replace(['\u{3002}', '\u{ff0e}', '\u{ff61}'], ".");
+        let mut labels = Vec::new();
+        for label in domain.split('.')
+        {
+            if label.is_ascii()
+            {
+                labels.push(label.to_string());
+            }
+            else
+            {
+                labels.push(String::from(PREFIX) + &punycode_encode(label)?);
+            }
+        }
+        encoded.push_str(&labels.join("."));
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let string = String::from_utf8_lossy(bytes);
+        let (local, domain) = split_address(&string);
+        let mut decoded = String::from(local);
+
+        let labels: Vec<String> = domain.split('.')
+            .map(|label|
+                {
+                    label.get(..PREFIX.len())
+                        .filter(|prefix| prefix.eq_ignore_ascii_case(PREFIX))
+                        .and_then(|_| punycode_decode(&label[PREFIX.len()..]))
+                        .unwrap_or(label.to_string())
+                })
+            .collect();
+        decoded.push_str(&labels.join("."));
+
+        decoded
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        std::str::from_utf8(bytes).ok()?;
+        Some(self.decode(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = IdnaEngine{};
+
+        let encoded = engine.encode("example.com").unwrap();
+        assert_eq!(encoded, b"example.com");
+
+        let encoded = engine.encode("clément.fr").unwrap();
+        assert_eq!(encoded, b"xn--clment-cva.fr");
+
+        let encoded = engine.encode("ミク。jp").unwrap();
+        assert_eq!(encoded, b"xn--pck1e.jp");
+
+        let encoded = engine.encode("clément@clément.fr").unwrap();
+        assert_eq!(encoded, "clément@xn--clment-cva.fr".as_bytes());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = IdnaEngine{};
+
+        let decoded = engine.decode(b"world.example");
+        assert_eq!(decoded, "world.example");
+
+        let decoded = engine.decode(b"XN--bcher-kva.de");
+        assert_eq!(decoded, "bücher.de");
+
+        let decoded = engine.decode(b"me@xn--pck1e.jp");
+        assert_eq!(decoded, "me@ミク.jp");
+
+        let decoded = engine.decode(b"xn--abc-!.jp");
+        assert_eq!(decoded, "xn--abc-!.jp");
+
+        let decoded = engine.try_decode(b"xn--abc-!.jp");
+        assert_eq!(decoded.unwrap(), "xn--abc-!.jp");
+
+        assert!(engine.try_decode(&[0xe9, b'.', b'f', b'r']).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = IdnaEngine{};
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -0,0 +1,282 @@
+//! Deencoding engine for ISO 6937 / T.61
+//!
+//! ISO 6937 is the teletext charset that T.61 is based on, which is still found
+//! in X.509 certificates (`TeletexString`) and old LDAP directories. Its upper
+//! half has a few symbols and letters, but accented letters are written as a
+//! non-spacing diacritic byte _preceding_ the base letter: `'é'` is `C2 65`,
+//! the acute accent followed by `'e'`.
+//!
+//! Decoding composes the diacritic with the following character with
+//! [`unicode-normalization`](https://crates.io/crates/unicode-normalization),
+//! or outputs the decomposed sequence if there is no precomposed character. A
+//! dangling diacritic at the end, and unassigned bytes, give U+FFFD �
+//! REPLACEMENT CHARACTER, or another character configured with
+//! [`Iso6937Engine::builder()`]. The T.61 positions of `'$'`, `'#'` and the
+//! umlaut are also decoded.
+//!
+//! Encoding decomposes characters that are not in the table into a base letter
+//! and a single diacritic, and fails if that is not possible.
+
+use crate::engine::{name_with_options, Engine};
+
+use unicode_normalization::char::{compose, decompose_canonical};
+
+pub struct Iso6937Engine
+{
+    /// The character to decode unassigned bytes as.
+    pub replacement: char
+}
+
+/// Builder for an [`Iso6937Engine`].
+pub struct Iso6937EngineBuilder
+{
+    engine: Iso6937Engine
+}
+
+impl Iso6937Engine
+{
+    /// The default engine, decoding unassigned bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Iso6937Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Iso6937EngineBuilder
+    {
+        Iso6937EngineBuilder { engine: Iso6937Engine::new() }
+    }
+
+    /// Decode, with unassigned bytes and a dangling diacritic as `replacement`,
+    /// or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let mut decoded = String::new();
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            let c = decode_byte(bytes[i]).or(replacement)?;
+            i += 1;
+            if !(0xc1..=0xcf).contains(&bytes[i - 1])
+            {
+                decoded.push(c);
+                continue;
+            }
+            // Diacritic, combine with the next character
+            let Some(&next) = bytes.get(i)
+            else
+            {
+                decoded.push(replacement?);
+                continue;
+            };
+            let base = decode_byte(next).or(replacement)?;
+            i += 1;
+            match compose(base, c)
+            {
+                Some(composed) => decoded.push(composed),
+                None =>
+                {
+                    decoded.push(base);
+                    decoded.push(c);
+                }
+            }
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Default for Iso6937Engine
+{
+    fn default() -> Self { Iso6937Engine::new() }
+}
+
+impl Iso6937EngineBuilder
+{
+    /// Set the character to decode unassigned bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Iso6937Engine { self.engine }
+}
+
+/// The upper half of ISO 6937, from `0xA0` to `0xFF`. Diacritics (`0xC1` to
+/// `0xCF`) are mapped to their combining counterparts, and unassigned bytes to
+/// U+0000.
+const UPPER: [char; 96] = [
+    // A0
+    '\u{a0}', '¡', '¢', '£', '\0', '¥', '\0', '§',
+    '¤', '‘', '“', '«', '←', '↑', '→', '↓',
+    // B0
+    '°', '±', '²', '³', '×', 'µ', '¶', '·',
+    '÷', '’', '”', '»', '¼', '½', '¾', '¿',
+    // C0
+    '\0', '\u{300}', '\u{301}', '\u{302}', '\u{303}', '\u{304}', '\u{306}',
+    '\u{307}', '\u{308}', '\0', '\u{30a}', '\u{327}', '\0', '\u{30b}',
+    '\u{328}', '\u{30c}',
+    // D0
+    '―', '¹', '®', '©', '™', '♪', '¬', '¦',
+    '\0', '\0', '\0', '\0', '⅛', '⅜', '⅝', '⅞',
+    // E0
+    'Ω', 'Æ', 'Đ', 'ª', 'Ħ', '\0', 'Ĳ', 'Ŀ',
+    'Ł', 'Ø', 'Œ', 'º', 'Þ', 'Ŧ', 'Ŋ', 'ŉ',
+    // F0
+    'ĸ', 'æ', 'đ', 'ð', 'ħ', 'ı', 'ĳ', 'ŀ',
+    'ł', 'ø', 'œ', 'ß', 'þ', 'ŧ', 'ŋ', '\u{ad}',
+];
+
+/// Decode a single byte, not taking diacritics into account, if assigned.
+fn decode_byte(byte: u8) -> Option<char>
+{
+    match byte
+    {
+        0x00..=0x9f => Some(byte as char),
+        // T.61 positions
+        0xa4 => Some('$'),
+        0xa6 => Some('#'),
+        0xc9 => Some('\u{308}'),
+        _ => match UPPER[(byte - 0xa0) as usize]
+        {
+            '\0' => None,
+            c => Some(c)
+        }
+    }
+}
+
+/// Encode a single character, without decomposition.
+fn encode_char(c: char) -> Option<u8>
+{
+    if (c as u32) < 0xa0
+    {
+        return Some(c as u8);
+    }
+    UPPER.iter()
+        .position(|&u| u == c && !('\u{300}'..='\u{36f}').contains(&c))
+        .map(|i| 0xa0 + i as u8)
+}
+
+impl Engine for Iso6937Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("ISO 6937 / T.61", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            if let Some(byte) = encode_char(c)
+            {
+                encoded.push(byte);
+                continue;
+            }
+            let mut decomposition = Vec::new();
+            decompose_canonical(c, |d| decomposition.push(d));
+            let [base, diacritic] = decomposition[..]
+            else
+            {
+                return None;
+            };
+            let prefix = UPPER[0x20..0x30].iter()
+                .position(|&u| u == diacritic)
+                .map(|i| 0xc0 + i as u8)?;
+            encoded.push(prefix);
+            encoded.push(encode_char(base)?);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        Iso6937Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Iso6937Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = Iso6937Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xc2, 0x65]);
+
+        let encoded = engine.encode("Łódź").unwrap();
+        assert_eq!(encoded, &[0xe8, 0xc2, 0x6f, 0x64, 0xc2, 0x7a]);
+
+        let encoded = engine.encode("ß½").unwrap();
+        assert_eq!(encoded, &[0xfb, 0xbd]);
+
+        assert!(engine.encode("€").is_none());
+        assert!(engine.encode("\u{301}").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = Iso6937Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xc1, 0x65]);
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(&[0xcf, 0x63, 0xc8, 0x71]);
+        // There is no precomposed q with diaeresis
+        assert_eq!(decoded, "čq\u{308}");
+
+        let decoded = engine.decode(&[0x61, 0xc2]);
+        assert_eq!(decoded, "a�");
+
+        let decoded = engine.decode(&[0xc0]);
+        assert_eq!(decoded, "�");
+
+        let engine = Iso6937Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "ISO 6937 / T.61 (errors as '?')");
+
+        let decoded = engine.decode(&[0xc0, 0x61, 0xc2]);
+        assert_eq!(decoded, "?a?");
+
+        assert!(engine.try_decode(&[0x61, 0xc2]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Iso6937Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,230 @@
+//! Deencoding engine for JSON/JavaScript string escapes
+//!
+//! Encoding outputs the body of a JSON string literal (without the surrounding
+//! quotes) in which every non-ASCII scalar is written as `\uXXXX` escapes of
+//! its UTF-16 units, so scalars outside the BMP become a surrogate pair, as in
+//! `"\ud83d\ude00"`. Quotes, backslashes and control characters are escaped
+//! as well, so chaining the engine twice reproduces double escaping:
+//! `"Clément"` becomes `"Cl\u00e9ment"`, then `"Cl\\u00e9ment"`.
+//!
+//! Decoding reads the bytes as UTF-8, then resolves escapes leniently, the way
+//! JavaScript does: an unknown escape such as `\q` stands for the escaped
+//! character itself. Malformed `\u` escapes and unpaired surrogates become
+//! U+FFFD � REPLACEMENT CHARACTER; strict decoding fails on them instead, but
+//! accepts a genuine `\ufffd`.
+
+use crate::engine::Engine;
+
+use std::borrow::Cow;
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub struct JsonEngine {}
+
+/// Append the `\uXXXX` escapes of the UTF-16 units of `c` to `output`.
+pub(crate) fn push_utf16_escapes(output: &mut String, c: char)
+{
+    let mut buf = [0u16; 2];
+    for unit in c.encode_utf16(&mut buf).iter()
+    {
+        output.push_str(&format!("\\u{:04x}", unit));
+    }
+}
+
+/// Read `count` hexadecimal digits from `chars`.
+///
+/// Nothing is consumed if there are not enough digits.
+pub(crate) fn read_hex(chars: &mut Peekable<Chars>, count: usize)
+    -> Option<u32>
+{
+    let digits: String = chars.clone().take(count).collect();
+    if digits.len() != count || !digits.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    for _ in 0..count
+    {
+        chars.next();
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+/// Resolve a `\uXXXX` escape whose `\u` has already been consumed from
+/// `chars`, pairing it with a following `\uXXXX` escape if it is a high
+/// surrogate.
+///
+/// Fail on malformed escapes and unpaired surrogates.
+pub(crate) fn read_utf16_escape(chars: &mut Peekable<Chars>) -> Option<char>
+{
+    let unit1 = read_hex(chars, 4)?;
+    if !(0xD800..0xDC00).contains(&unit1)
+    {
+        return char::from_u32(unit1);
+    }
+    // High surrogate, look for the low surrogate
+    let mut lookahead = chars.clone();
+    if lookahead.next() != Some('\\') || lookahead.next() != Some('u')
+    {
+        return None;
+    }
+    match read_hex(&mut lookahead, 4)
+    {
+        Some(unit2) if (0xDC00..0xE000).contains(&unit2) =>
+        {
+            *chars = lookahead;
+            char::decode_utf16([unit1 as u16, unit2 as u16])
+                .next()
+                .and_then(|r| r.ok())
+        }
+        _ => None
+    }
+}
+
+impl JsonEngine
+{
+    /// Decode, with invalid UTF-8, malformed escapes and unpaired surrogates
+    /// as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let string = match replacement
+        {
+            Some(_) => String::from_utf8_lossy(bytes),
+            None => Cow::Borrowed(std::str::from_utf8(bytes).ok()?)
+        };
+        let mut decoded = String::new();
+
+        let mut chars = string.chars().peekable();
+        while let Some(c) = chars.next()
+        {
+            if c != '\\'
+            {
+                decoded.push(c);
+                continue;
+            }
+            match chars.next()
+            {
+                Some('b') => decoded.push('\u{08}'),
+                Some('f') => decoded.push('\u{0c}'),
+                Some('n') => decoded.push('\n'),
+                Some('r') => decoded.push('\r'),
+                Some('t') => decoded.push('\t'),
+                Some('u') => decoded.push(
+                    read_utf16_escape(&mut chars).or(replacement)?),
+                Some(other) => decoded.push(other),
+                // Cannot read the escaped character
+                None => decoded.push(replacement?)
+            }
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Engine for JsonEngine
+{
+    fn get_name(&self) -> String { "JSON escapes".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = String::new();
+
+        for c in string.chars()
+        {
+            match c
+            {
+                '"' => encoded.push_str("\\\""),
+                '\\' => encoded.push_str("\\\\"),
+                '\u{08}' => encoded.push_str("\\b"),
+                '\u{0c}' => encoded.push_str("\\f"),
+                '\n' => encoded.push_str("\\n"),
+                '\r' => encoded.push_str("\\r"),
+                '\t' => encoded.push_str("\\t"),
+                _ if c.is_ascii() && !c.is_ascii_control() => encoded.push(c),
+                _ => push_utf16_escapes(&mut encoded, c)
+            }
+        }
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        JsonEngine::decode_with(bytes, Some(char::REPLACEMENT_CHARACTER))
+            .unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        JsonEngine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = JsonEngine{};
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"\\u00e9");
+
+        let encoded = engine.encode("\"\\\n").unwrap();
+        assert_eq!(encoded, b"\\\"\\\\\\n");
+
+        let encoded = engine.encode("😀").unwrap();
+        // U+1F600
+        // => U+D83D U+DE00
+        assert_eq!(encoded, b"\\ud83d\\ude00");
+
+        let encoded = engine.encode("\\u00e9").unwrap();
+        assert_eq!(encoded, b"\\\\u00e9");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = JsonEngine{};
+
+        let decoded = engine.decode(b"world\\u0021");
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"\\u00E8");
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(b"\\ud83d\\ude10");
+        // U+D83D U+DE10
+        // => U+1F610
+        assert_eq!(decoded, "😐");
+
+        let decoded = engine.decode(b"\\ud83d!");
+        assert_eq!(decoded, "�!");
+
+        let decoded = engine.decode(b"\\u00");
+        assert_eq!(decoded, "�00");
+
+        assert!(engine.try_decode(b"\\ud83d!").is_none());
+        assert!(engine.try_decode(&[0xff]).is_none());
+
+        let decoded = engine.try_decode(b"\\ufffd");
+        assert_eq!(decoded.unwrap(), "�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = JsonEngine{};
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -8,17 +8,83 @@
 //! support insertion of U+FFFD � REPLACEMENT CHARACTER at decoding. Decoding is
 //! performed with [`mail-parser`](https://crates.io/crates/mail-parser), which
 //! does not allow encoding.
+//!
+//! The undefined bytes are decoded as U+FFFD � REPLACEMENT CHARACTER by
+//! default, or as another character configured with
+//! [`Latin1Engine::builder()`].
 
-use crate::engine::Engine;
+use crate::engine::{name_with_options, Engine};
 
 use encoding_rs::*;
 use mail_parser::*;
 
-pub struct Latin1Engine {}
+pub struct Latin1Engine
+{
+    /// The character to decode undefined bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`Latin1Engine`].
+pub struct Latin1EngineBuilder
+{
+    engine: Latin1Engine
+}
+
+impl Latin1Engine
+{
+    /// The default engine, decoding undefined bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Latin1Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Latin1EngineBuilder
+    {
+        Latin1EngineBuilder { engine: Latin1Engine::new() }
+    }
+
+    /// Decode, with undefined bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        decoders::charsets::single_byte::decoder_cp1252(bytes)
+            .chars()
+            .map(|c|
+                if c == char::REPLACEMENT_CHARACTER { replacement }
+                else { Some(c) })
+            .collect()
+    }
+}
+
+impl Default for Latin1Engine
+{
+    fn default() -> Self { Latin1Engine::new() }
+}
+
+impl Latin1EngineBuilder
+{
+    /// Set the character to decode undefined bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Latin1Engine { self.engine }
+}
 
 impl Engine for Latin1Engine
 {
-    fn get_name(&self) -> String { "Latin-1 / Codepage 1252".to_string() }
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("Latin-1 / Codepage 1252", &options)
+    }
 
     fn encode(&self, string: &str) -> Option<Vec<u8>>
     {
@@ -35,7 +101,13 @@ impl Engine for Latin1Engine
 
     fn decode(&self, bytes: &[u8]) -> String
     {
-        decoders::charsets::single_byte::decoder_cp1252(bytes)
+        // Cannot fail with a replacement
+        Latin1Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Latin1Engine::decode_with(bytes, None)
     }
 }
 
@@ -43,9 +115,11 @@ impl Engine for Latin1Engine
 mod tests {
     use super::*;
 
+    use crate::engine::conformance;
+
     #[test]
     fn encode() {
-        let engine = Latin1Engine{};
+        let engine = Latin1Engine::new();
 
         let encoded = engine.encode("Hello").unwrap();
         assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
@@ -62,7 +136,7 @@ mod tests {
     #[test]
     fn decode()
     {
-        let engine = Latin1Engine{};
+        let engine = Latin1Engine::new();
 
         let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
         assert_eq!(decoded, "world!");
@@ -72,5 +146,26 @@ mod tests {
 
         let decoded = engine.decode(&[0x81]);
         assert_eq!(decoded, "�");
+
+        let engine = Latin1Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(),
+            "Latin-1 / Codepage 1252 (errors as '?')");
+
+        let decoded = engine.decode(&[0x43, 0x81, 0xe9]);
+        assert_eq!(decoded, "C?é");
+
+        assert!(engine.try_decode(&[0x43, 0x81, 0xe9]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Latin1Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
     }
 }
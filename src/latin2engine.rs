@@ -9,17 +9,83 @@
 //! support insertion of U+FFFD � REPLACEMENT CHARACTER at decoding. Decoding is
 //! performed with [`mail-parser`](https://crates.io/crates/mail-parser), which
 //! does not allow encoding.
+//!
+//! The undefined bytes are decoded as U+FFFD � REPLACEMENT CHARACTER by
+//! default, or as another character configured with
+//! [`Latin2Engine::builder()`].
 
-use crate::engine::Engine;
+use crate::engine::{name_with_options, Engine};
 
 use encoding_rs::*;
 use mail_parser::*;
 
-pub struct Latin2Engine {}
+pub struct Latin2Engine
+{
+    /// The character to decode undefined bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`Latin2Engine`].
+pub struct Latin2EngineBuilder
+{
+    engine: Latin2Engine
+}
+
+impl Latin2Engine
+{
+    /// The default engine, decoding undefined bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Latin2Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Latin2EngineBuilder
+    {
+        Latin2EngineBuilder { engine: Latin2Engine::new() }
+    }
+
+    /// Decode, with undefined bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        decoders::charsets::single_byte::decoder_cp1250(bytes)
+            .chars()
+            .map(|c|
+                if c == char::REPLACEMENT_CHARACTER { replacement }
+                else { Some(c) })
+            .collect()
+    }
+}
+
+impl Default for Latin2Engine
+{
+    fn default() -> Self { Latin2Engine::new() }
+}
+
+impl Latin2EngineBuilder
+{
+    /// Set the character to decode undefined bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Latin2Engine { self.engine }
+}
 
 impl Engine for Latin2Engine
 {
-    fn get_name(&self) -> String { "Latin-2 / Codepage 1250".to_string() }
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("Latin-2 / Codepage 1250", &options)
+    }
 
     fn encode(&self, string: &str) -> Option<Vec<u8>>
     {
@@ -36,7 +102,13 @@ impl Engine for Latin2Engine
 
     fn decode(&self, bytes: &[u8]) -> String
     {
-        decoders::charsets::single_byte::decoder_cp1250(bytes)
+        // Cannot fail with a replacement
+        Latin2Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Latin2Engine::decode_with(bytes, None)
     }
 }
 
@@ -44,9 +116,11 @@ impl Engine for Latin2Engine
 mod tests {
     use super::*;
 
+    use crate::engine::conformance;
+
     #[test]
     fn encode() {
-        let engine = Latin2Engine{};
+        let engine = Latin2Engine::new();
 
         let encoded = engine.encode("Hello").unwrap();
         assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
@@ -60,7 +134,7 @@ mod tests {
     #[test]
     fn decode()
     {
-        let engine = Latin2Engine{};
+        let engine = Latin2Engine::new();
 
         let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
         assert_eq!(decoded, "world!");
@@ -70,5 +144,26 @@ mod tests {
 
         let decoded = engine.decode(&[0x81]);
         assert_eq!(decoded, "�");
+
+        let engine = Latin2Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(),
+            "Latin-2 / Codepage 1250 (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x81, 0x42]);
+        assert_eq!(decoded, "A?B");
+
+        assert!(engine.try_decode(&[0x41, 0x81, 0x42]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Latin2Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
     }
 }
@@ -10,7 +10,15 @@
 //! * [Engines](engine/trait.Engine.html) are objects that represent an encoding
 //!   scheme, and can be used to encode (String to bytes) or decode (bytes to
 //!   String). A number of engines are already implemented into this crate, with
-//!   static instances if you want to use them.
+//!   static instances if you want to use them. The character set engines have
+//!   a `new()` default, and a builder to configure them, such as
+//!   `Utf8Engine::builder().bom(true).strict(false).build()`, which can set
+//!   the character that invalid input decodes as when there is any; escape
+//!   formats and engines wrapping other engines are configured with their
+//!   public fields instead. The static instances use the default
+//!   configuration, apart from the byte order mark of the `BOM` ones and the
+//!   packed GSM one, and so does the provided executable, since builders are
+//!   only available from the library.
 //! * The structure of deencoding is a
 //!   [tree](deencodetree/struct.DeencodeTree.html): from an input string, every
 //!   engine may give an encoding, then every engine gives a decoding of that
@@ -40,39 +48,304 @@
 
 pub mod deencodetree;
 pub mod engine;
+pub mod bestfitengine;
+pub mod bocu1engine;
+pub mod bomengine;
+pub mod cescapeengine;
+pub mod chainengine;
 pub mod cp1253engine;
 pub mod cp1254engine;
 pub mod cp1255engine;
+pub mod cp1256engine;
+pub mod cp1257engine;
+pub mod cp1258engine;
+pub mod cp874engine;
+pub mod fnengine;
+pub mod gsmengine;
+pub mod htmlengine;
+pub mod hybridengine;
+pub mod idnaengine;
+pub mod iso6937engine;
+pub mod jsonengine;
 pub mod latin1engine;
 pub mod latin2engine;
+pub mod marc8engine;
+pub mod misalignedengine;
 pub mod mixed816beengine;
 pub mod mixed816leengine;
+pub mod native2asciiengine;
+pub mod normalizedengine;
+pub mod punycodeengine;
+pub mod pythonengine;
+pub mod questionmarkengine;
+pub mod rtfengine;
+pub mod scsuengine;
+pub mod sevenbitengine;
+pub mod sloppyengine;
+pub mod tableengine;
+pub mod tcvn3engine;
+pub mod translitengine;
+pub mod truncatedengine;
+pub mod uppercaseengine;
+pub mod utf16engine;
 pub mod utf7engine;
 pub mod utf8engine;
+pub mod visciiengine;
+pub mod vniengine;
 
 pub use engine::Engine;
 pub use deencodetree::DeencodeTree;
 
+/// Provided engine for BOCU-1.
+pub static BOCU1: bocu1engine::Bocu1Engine = bocu1engine::Bocu1Engine::new();
+/// Provided engine for C-style `\xNN` escapes over bytes.
+pub static CESCAPE: cescapeengine::CEscapeEngine =
+    cescapeengine::CEscapeEngine {};
 /// Provided engine ISO-8859-7 / Codepage 1253.
-pub static CP1253: cp1253engine::CP1253Engine = cp1253engine::CP1253Engine {};
+pub static CP1253: cp1253engine::CP1253Engine =
+    cp1253engine::CP1253Engine::new();
+/// Provided engine for Codepage 1253 with Windows best fit.
+pub static CP1253BESTFIT:
+    bestfitengine::BestFitEngine<cp1253engine::CP1253Engine> =
+    bestfitengine::BestFitEngine {
+        engine: &CP1253, table: bestfitengine::CP1253
+    };
+/// Provided engine for Codepage 1253, keeping undefined bytes.
+pub static CP1253SLOPPY:
+    sloppyengine::SloppyEngine<cp1253engine::CP1253Engine> =
+    sloppyengine::SloppyEngine { engine: &CP1253 };
 /// Provided engine ISO-8859-9 / Codepage 1254.
-pub static CP1254: cp1254engine::CP1254Engine = cp1254engine::CP1254Engine {};
+pub static CP1254: cp1254engine::CP1254Engine =
+    cp1254engine::CP1254Engine::new();
+/// Provided engine for Codepage 1254 with Windows best fit.
+pub static CP1254BESTFIT:
+    bestfitengine::BestFitEngine<cp1254engine::CP1254Engine> =
+    bestfitengine::BestFitEngine {
+        engine: &CP1254, table: bestfitengine::CP1254
+    };
+/// Provided engine for ISO-8859-9 / Codepage 1254, keeping undefined bytes.
+pub static CP1254SLOPPY:
+    sloppyengine::SloppyEngine<cp1254engine::CP1254Engine> =
+    sloppyengine::SloppyEngine { engine: &CP1254 };
+/// Provided engine for ISO-8859-9 / Codepage 1254, with Turkish upper-casing.
+pub static CP1254UPPER:
+    uppercaseengine::UppercaseEngine<cp1254engine::CP1254Engine> =
+    uppercaseengine::UppercaseEngine {
+        uppercase: uppercaseengine::Uppercase::Turkish, engine: &CP1254
+    };
 /// Provided engine ISO-8859-8 / Codepage 1255.
-pub static CP1255: cp1255engine::CP1255Engine = cp1255engine::CP1255Engine {};
+pub static CP1255: cp1255engine::CP1255Engine =
+    cp1255engine::CP1255Engine::new();
+/// Provided engine for Codepage 1255 with Windows best fit.
+pub static CP1255BESTFIT:
+    bestfitengine::BestFitEngine<cp1255engine::CP1255Engine> =
+    bestfitengine::BestFitEngine {
+        engine: &CP1255, table: bestfitengine::CP1255
+    };
+/// Provided engine for ISO-8859-8 / Codepage 1255, keeping undefined bytes.
+pub static CP1255SLOPPY:
+    sloppyengine::SloppyEngine<cp1255engine::CP1255Engine> =
+    sloppyengine::SloppyEngine { engine: &CP1255 };
+/// Provided engine for Codepage 1256.
+pub static CP1256: cp1256engine::CP1256Engine =
+    cp1256engine::CP1256Engine::new();
+/// Provided engine for Codepage 1257.
+pub static CP1257: cp1257engine::CP1257Engine =
+    cp1257engine::CP1257Engine::new();
+/// Provided engine for Codepage 1257, keeping undefined bytes.
+pub static CP1257SLOPPY:
+    sloppyengine::SloppyEngine<cp1257engine::CP1257Engine> =
+    sloppyengine::SloppyEngine { engine: &CP1257 };
+/// Provided engine for Codepage 1258.
+pub static CP1258: cp1258engine::CP1258Engine =
+    cp1258engine::CP1258Engine::new();
+/// Provided engine for Codepage 1258, keeping undefined bytes.
+pub static CP1258SLOPPY:
+    sloppyengine::SloppyEngine<cp1258engine::CP1258Engine> =
+    sloppyengine::SloppyEngine { engine: &CP1258 };
+/// Provided engine for Codepage 874.
+pub static CP874: cp874engine::CP874Engine = cp874engine::CP874Engine::new();
+/// Provided engine for the GSM 03.38 SMS alphabet, one septet per byte.
+pub static GSM: gsmengine::GsmEngine = gsmengine::GsmEngine::new();
+/// Provided engine for the GSM 03.38 SMS alphabet, with packed septets.
+pub static GSMPACKED: gsmengine::GsmEngine =
+    gsmengine::GsmEngine::builder().packed(true).build();
+/// Provided engine for HTML/XML named character references.
+pub static HTMLNAMED: htmlengine::HtmlEngine =
+    htmlengine::HtmlEngine { named: true };
+/// Provided engine for HTML/XML numeric character references.
+pub static HTMLNUMERIC: htmlengine::HtmlEngine =
+    htmlengine::HtmlEngine { named: false };
+/// Provided engine for IDNA labels.
+pub static IDNA: idnaengine::IdnaEngine = idnaengine::IdnaEngine {};
+/// Provided engine for ISO 6937 / T.61.
+pub static ISO6937: iso6937engine::Iso6937Engine =
+    iso6937engine::Iso6937Engine::new();
+/// Provided engine for JSON/JavaScript string escapes.
+pub static JSON: jsonengine::JsonEngine = jsonengine::JsonEngine {};
 /// Provided engine for Latin-1 / ISO-8859-1 / Codepage 1252.
-pub static LATIN1: latin1engine::Latin1Engine = latin1engine::Latin1Engine {};
+pub static LATIN1: latin1engine::Latin1Engine =
+    latin1engine::Latin1Engine::new();
+/// Provided engine for Codepage 1252 with Windows best fit.
+pub static LATIN1BESTFIT:
+    bestfitengine::BestFitEngine<latin1engine::Latin1Engine> =
+    bestfitengine::BestFitEngine {
+        engine: &LATIN1, table: bestfitengine::CP1252
+    };
+/// Provided engine for Latin-1 / ISO-8859-1 / Codepage 1252, keeping undefined
+/// bytes.
+pub static LATIN1SLOPPY:
+    sloppyengine::SloppyEngine<latin1engine::Latin1Engine> =
+    sloppyengine::SloppyEngine { engine: &LATIN1 };
 /// Provided engine for Latin-2 / ISO-8859-2 / Codepage 1250.
-pub static LATIN2: latin2engine::Latin2Engine = latin2engine::Latin2Engine {};
+pub static LATIN2: latin2engine::Latin2Engine =
+    latin2engine::Latin2Engine::new();
+/// Provided engine for Codepage 1250 with Windows best fit.
+pub static LATIN2BESTFIT:
+    bestfitengine::BestFitEngine<latin2engine::Latin2Engine> =
+    bestfitengine::BestFitEngine {
+        engine: &LATIN2, table: bestfitengine::CP1250
+    };
+/// Provided engine for Latin-2 / ISO-8859-2 / Codepage 1250, keeping undefined
+/// bytes.
+pub static LATIN2SLOPPY:
+    sloppyengine::SloppyEngine<latin2engine::Latin2Engine> =
+    sloppyengine::SloppyEngine { engine: &LATIN2 };
+/// Provided engine for MARC-8 / ANSEL.
+pub static MARC8: marc8engine::Marc8Engine = marc8engine::Marc8Engine::new();
 /// Provided engine for a mixed UTF-8/UTF-16BE scheme.
 pub static MIXED816BE: mixed816beengine::Mixed816BEEngine =
-    mixed816beengine::Mixed816BEEngine {};
+    mixed816beengine::Mixed816BEEngine::new();
+/// Provided engine for a mixed UTF-8/UTF-16BE scheme, one byte out of
+/// alignment.
+pub static MIXED816BEODD:
+    misalignedengine::MisalignedEngine<mixed816beengine::Mixed816BEEngine> =
+    misalignedengine::MisalignedEngine {
+        misalignment: misalignedengine::Misalignment::OddOffsetDrop,
+        engine: &MIXED816BE
+    };
+/// Provided engine for a mixed UTF-8/UTF-16BE scheme, with swapped bytes.
+pub static MIXED816BESWAPPED:
+    misalignedengine::MisalignedEngine<mixed816beengine::Mixed816BEEngine> =
+    misalignedengine::MisalignedEngine {
+        misalignment: misalignedengine::Misalignment::Swapped,
+        engine: &MIXED816BE
+    };
 /// Provided engine for a mixed UTF-8/UTF-16LE scheme.
 pub static MIXED816LE: mixed816leengine::Mixed816LEEngine =
-    mixed816leengine::Mixed816LEEngine {};
+    mixed816leengine::Mixed816LEEngine::new();
+/// Provided engine for a mixed UTF-8/UTF-16LE scheme, one byte out of
+/// alignment.
+pub static MIXED816LEODD:
+    misalignedengine::MisalignedEngine<mixed816leengine::Mixed816LEEngine> =
+    misalignedengine::MisalignedEngine {
+        misalignment: misalignedengine::Misalignment::OddOffsetDrop,
+        engine: &MIXED816LE
+    };
+/// Provided engine for a mixed UTF-8/UTF-16LE scheme, with swapped bytes.
+pub static MIXED816LESWAPPED:
+    misalignedengine::MisalignedEngine<mixed816leengine::Mixed816LEEngine> =
+    misalignedengine::MisalignedEngine {
+        misalignment: misalignedengine::Misalignment::Swapped,
+        engine: &MIXED816LE
+    };
+/// Provided engine for Java `native2ascii` / `.properties` escapes.
+pub static NATIVE2ASCII: native2asciiengine::Native2AsciiEngine =
+    native2asciiengine::Native2AsciiEngine {};
+/// Provided engine for Punycode.
+pub static PUNYCODE: punycodeengine::PunycodeEngine =
+    punycodeengine::PunycodeEngine {};
+/// Provided engine for Python's `unicode_escape` codec.
+pub static PYTHON: pythonengine::PythonEngine = pythonengine::PythonEngine {};
+/// Provided engine for ASCII-only systems replacing each non-ASCII byte of
+/// UTF-8 with a `?`.
+pub static QUESTIONMARKBYTE:
+    questionmarkengine::QuestionMarkEngine<utf8engine::Utf8Engine> =
+    questionmarkengine::QuestionMarkEngine {
+        replacement: questionmarkengine::Replacement::Byte, engine: &UTF8
+    };
+/// Provided engine for ASCII-only systems replacing each non-ASCII character
+/// of UTF-8 with a `?`.
+pub static QUESTIONMARKCHAR:
+    questionmarkengine::QuestionMarkEngine<utf8engine::Utf8Engine> =
+    questionmarkengine::QuestionMarkEngine {
+        replacement: questionmarkengine::Replacement::Character, engine: &UTF8
+    };
+/// Provided engine for RTF text escapes, with `\ansicpg1252`.
+pub static RTF: rtfengine::RtfEngine<latin1engine::Latin1Engine> =
+    rtfengine::RtfEngine { engine: &LATIN1 };
+/// Provided engine for SCSU.
+pub static SCSU: scsuengine::ScsuEngine = scsuengine::ScsuEngine::new();
+/// Provided engine for 7-bit transports.
+pub static SEVENBIT: sevenbitengine::SevenBitEngine =
+    sevenbitengine::SevenBitEngine::new();
+/// Provided engine for TCVN3 (ABC).
+pub static TCVN3: tcvn3engine::Tcvn3Engine = tcvn3engine::Tcvn3Engine::new();
+/// Provided engine for ASCII transliteration.
+pub static TRANSLIT: translitengine::TranslitEngine =
+    translitengine::TranslitEngine::new();
+/// Provided engine for UTF-16BE.
+pub static UTF16BE: utf16engine::Utf16Engine =
+    utf16engine::Utf16Engine::new(utf16engine::Endianness::Big);
+/// Provided engine for UTF-16BE with a byte order mark.
+pub static UTF16BEBOM: utf16engine::Utf16Engine =
+    utf16engine::Utf16Engine::builder().bom(true).build();
+/// Provided engine for UTF-16BE, one byte out of alignment.
+pub static UTF16BEODD:
+    misalignedengine::MisalignedEngine<utf16engine::Utf16Engine> =
+    misalignedengine::MisalignedEngine {
+        misalignment: misalignedengine::Misalignment::OddOffsetDrop,
+        engine: &UTF16BE
+    };
+/// Provided engine for UTF-16LE.
+pub static UTF16LE: utf16engine::Utf16Engine =
+    utf16engine::Utf16Engine::new(utf16engine::Endianness::Little);
+/// Provided engine for UTF-16LE with a byte order mark.
+pub static UTF16LEBOM: utf16engine::Utf16Engine =
+    utf16engine::Utf16Engine::builder()
+        .endianness(utf16engine::Endianness::Little)
+        .bom(true)
+        .build();
+/// Provided engine for UTF-16LE, one byte out of alignment.
+pub static UTF16LEODD:
+    misalignedengine::MisalignedEngine<utf16engine::Utf16Engine> =
+    misalignedengine::MisalignedEngine {
+        misalignment: misalignedengine::Misalignment::OddOffsetDrop,
+        engine: &UTF16LE
+    };
 /// Provided engine for UTF-7.
-pub static UTF7: utf7engine::Utf7Engine = utf7engine::Utf7Engine {};
+pub static UTF7: utf7engine::Utf7Engine = utf7engine::Utf7Engine::new();
 /// Provided engine for UTF-8.
-pub static UTF8: utf8engine::Utf8Engine = utf8engine::Utf8Engine {};
+pub static UTF8: utf8engine::Utf8Engine = utf8engine::Utf8Engine::new();
+/// Provided engine for UTF-8, with ASCII-only upper-casing of the bytes.
+pub static UTF8ASCIIUPPER:
+    uppercaseengine::UppercaseEngine<utf8engine::Utf8Engine> =
+    uppercaseengine::UppercaseEngine {
+        uppercase: uppercaseengine::Uppercase::AsciiBytes, engine: &UTF8
+    };
+/// Provided engine for UTF-8 with a byte order mark.
+pub static UTF8BOM: utf8engine::Utf8Engine =
+    utf8engine::Utf8Engine::builder().bom(true).build();
+/// Provided engine for UTF-8, falling back to Latin-1 / Codepage 1252 for
+/// invalid bytes.
+pub static UTF8LATIN1: hybridengine::HybridEngine<latin1engine::Latin1Engine> =
+    hybridengine::HybridEngine { engine: &LATIN1 };
+/// Provided engine for UTF-8 in decomposed form (NFD), as used by macOS for
+/// filenames.
+pub static UTF8NFD: normalizedengine::NormalizedEngine<utf8engine::Utf8Engine>
+    = normalizedengine::NormalizedEngine {
+        form: normalizedengine::NormalizationForm::NFD, engine: &UTF8
+    };
+/// Provided engine for UTF-8, with Unicode upper-casing.
+pub static UTF8UPPER: uppercaseengine::UppercaseEngine<utf8engine::Utf8Engine> =
+    uppercaseengine::UppercaseEngine {
+        uppercase: uppercaseengine::Uppercase::Unicode, engine: &UTF8
+    };
+/// Provided engine for VISCII.
+pub static VISCII: visciiengine::VisciiEngine =
+    visciiengine::VisciiEngine::new();
+/// Provided engine for VNI.
+pub static VNI: vniengine::VniEngine = vniengine::VniEngine::new();
 
 /// Build a [`DeencodeTree`] by successively running encodings and decodings
 /// through the engines.
@@ -93,3 +366,13 @@ pub fn deencode(input: &str, engines: &[&dyn Engine], encoding_depth: usize)
 {
     DeencodeTree::deencode(input, engines, encoding_depth)
 }
+
+/// Like [`deencode()`], but pruning the decodings that would need replacement
+/// characters, and recording how many were pruned. See
+/// [`DeencodeTree::deencode_strict()`].
+pub fn deencode_strict(input: &str, engines: &[&dyn Engine],
+    encoding_depth: usize)
+    -> DeencodeTree
+{
+    DeencodeTree::deencode_strict(input, engines, encoding_depth)
+}
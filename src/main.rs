@@ -1,18 +1,84 @@
 use deencode::*;
+use deencode::tableengine::TableEngine;
 
-fn main()
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode
 {
-    let engines: Vec<&dyn Engine> = vec![
-        // Most standard encoding
-        &UTF8,
+    // Engines loaded from mapping files with `--table FILE`
+    let mut tables = Vec::<TableEngine>::new();
+    let mut inputs = Vec::<String>::new();
+    // Pruning of decodings needing replacement characters with `--strict`
+    let mut strict = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next()
+    {
+        if arg == "--strict"
+        {
+            strict = true;
+            continue;
+        }
+        if arg != "--table"
+        {
+            inputs.push(arg);
+            continue;
+        }
+        let Some(path) = args.next()
+        else
+        {
+            eprintln!("--table requires a mapping file");
+            return ExitCode::FAILURE;
+        };
+        match TableEngine::from_file(Path::new(&path))
+        {
+            Ok(table) => tables.push(table),
+            Err(error) =>
+            {
+                eprintln!("{}: {}", path, error);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut engines: Vec<&dyn Engine> = vec![
+        // Most standard encodings
+        &UTF8, &UTF16BE, &UTF16LE,
+        // Byte order marks
+        &UTF8BOM, &UTF16BEBOM, &UTF16LEBOM,
+        // UTF-8 readers falling back to a legacy code page
+        &UTF8LATIN1,
         // Single byte encodings
-        &LATIN1, &LATIN2, &CP1253, &CP1254, &CP1255,
+        &LATIN1, &LATIN2, &CP1253, &CP1254, &CP1255, &CP1256, &CP1257, &CP1258,
+        &CP874,
+        // Sloppy code pages keeping undefined bytes
+        &LATIN1SLOPPY,
+        // Vietnamese legacy encodings
+        &VISCII, &TCVN3, &VNI,
+        // Upper-case-only systems
+        &UTF8UPPER, &UTF8ASCIIUPPER, &CP1254UPPER,
+        // Compressed Unicode
+        &SCSU, &BOCU1,
+        // Lossy ASCII-only systems
+        &TRANSLIT, &SEVENBIT, &QUESTIONMARKBYTE, &QUESTIONMARKCHAR,
         // My weird encodings that cause problems on purpose
         &MIXED816BE, &MIXED816LE];
+    engines.extend(tables.iter().map(|table| table as &dyn Engine));
 
-    for arg in std::env::args().skip(1) {
-        let mut tree = deencode(&arg, &engines, 1);
+    for input in inputs
+    {
+        let mut tree = if strict
+        {
+            deencode_strict(&input, &engines, 1)
+        }
+        else
+        {
+            deencode(&input, &engines, 1)
+        };
         let _ = tree.deduplicate();
         println!("{}", tree);
     }
+
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,540 @@
+//! Deencoding engine for MARC-8 / ANSEL
+//!
+//! MARC-8 is the charset of MARC 21 library catalogue records. By default, its
+//! lower half is ASCII and its upper half is ANSEL (Extended Latin), in which
+//! accented letters are written as one or more combining diacritics
+//! _preceding_ the base letter: `'é'` is `E2 65`, the acute accent followed by
+//! `'e'`.
+//!
+//! Other character sets are designated into G0 (lower half) or G1 (upper
+//! half) with escape sequences: for example `ESC ( N` switches G0 to Basic
+//! Cyrillic, and `ESC s` switches back to ASCII. This engine implements the
+//! Basic Cyrillic and Basic Greek sets, and the Greek symbols, subscripts and
+//! superscripts sets. Like ANSEL, Basic Greek writes its accents and breathings
+//! as combining marks preceding the base letter. The escape sequences of the
+//! other sets (Arabic, Hebrew, Extended Cyrillic) are recognised, so that the
+//! following bytes are not misread, but their characters decode as U+FFFD �
+//! REPLACEMENT CHARACTER, and cannot be encoded.
+//!
+//! CJK is out of scope: the 3-byte EACC set maps some 16,000 characters, whose
+//! table this crate does not carry. Its escape sequences are recognised as
+//! well, and each 3-byte character decodes as a single U+FFFD � REPLACEMENT
+//! CHARACTER, so that Latin text around CJK names is still decoded. Records
+//! with CJK should be requested from the catalogue in UTF-8 instead.
+//!
+//! Decoding composes diacritics with their base letter with
+//! [`unicode-normalization`](https://crates.io/crates/unicode-normalization).
+//! Dangling diacritics at the end decode as U+FFFD � REPLACEMENT CHARACTER.
+//! Another character can be configured with [`Marc8Engine::builder()`].
+//! Encoding decomposes characters that are not in the tables into a base
+//! letter and diacritics, and fails if that is not possible. It leaves G0 set
+//! to ASCII at the end, as MARC 21 requires.
+
+use crate::engine::{name_with_options, Engine};
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::decompose_canonical;
+
+const ESC: u8 = 0x1b;
+
+/// The spacing characters of ANSEL, from `0xA1` to `0xC8`. Unassigned bytes
+/// are mapped to U+0000.
+const ANSEL: [char; 40] = [
+    'Ł', 'Ø', 'Đ', 'Þ', 'Æ', 'Œ', 'ʹ', '·', '♭', '®', '±', 'Ơ', 'Ư', 'ʼ', '\0',
+    'ʻ', 'ł', 'ø', 'đ', 'þ', 'æ', 'œ', 'ʺ', 'ı', '£', 'ð', '\0', 'ơ', 'ư',
+    '\0', '\0', '°', 'ℓ', '℗', '©', '♯', '¿', '¡', 'ß', '€',
+];
+
+/// The combining diacritics of ANSEL, from `0xE0` to `0xFE`. Unassigned bytes
+/// are mapped to U+0000.
+const ANSEL_DIACRITICS: [char; 31] = [
+    '\u{309}', '\u{300}', '\u{301}', '\u{302}', '\u{303}', '\u{304}',
+    '\u{306}', '\u{307}', '\u{308}', '\u{30c}', '\u{30a}', '\u{fe20}',
+    '\u{fe21}', '\u{315}', '\u{30b}', '\u{310}', '\u{327}', '\u{328}',
+    '\u{323}', '\u{324}', '\u{325}', '\u{333}', '\u{332}', '\u{326}',
+    '\u{31c}', '\u{32e}', '\u{fe22}', '\u{fe23}', '\0', '\0', '\u{313}',
+];
+
+/// The letters of Basic Cyrillic, from `0x40` to `0x7E`.
+const CYRILLIC: [char; 63] = [
+    'ю', 'а', 'б', 'ц', 'д', 'е', 'ф', 'г', 'х', 'и', 'й', 'к', 'л', 'м', 'н',
+    'о', 'п', 'я', 'р', 'с', 'т', 'у', 'ж', 'в', 'ь', 'ы', 'з', 'ш', 'э', 'щ',
+    'ч', 'ъ', 'Ю', 'А', 'Б', 'Ц', 'Д', 'Е', 'Ф', 'Г', 'Х', 'И', 'Й', 'К', 'Л',
+    'М', 'Н', 'О', 'П', 'Я', 'Р', 'С', 'Т', 'У', 'Ж', 'В', 'Ь', 'Ы', 'З', 'Ш',
+    'Э', 'Щ', 'Ч',
+];
+
+/// The letters and punctuation of Basic Greek, with their bytes.
+const GREEK: [(u8, char); 66] = [
+    (0x30, '«'), (0x31, '»'), (0x32, '“'), (0x33, '”'), (0x34, '\u{374}'),
+    (0x35, '\u{375}'), (0x3b, '\u{387}'), (0x3f, '\u{37e}'), (0x41, 'Α'),
+    (0x42, 'Β'), (0x44, 'Γ'), (0x45, 'Δ'), (0x46, 'Ε'), (0x47, 'Ϛ'),
+    (0x48, 'Ϝ'), (0x49, 'Ζ'), (0x4a, 'Η'), (0x4b, 'Θ'), (0x4c, 'Ι'),
+    (0x4d, 'Κ'), (0x4e, 'Λ'), (0x4f, 'Μ'), (0x50, 'Ν'), (0x51, 'Ξ'),
+    (0x52, 'Ο'), (0x53, 'Π'), (0x54, 'Ϟ'), (0x55, 'Ρ'), (0x56, 'Σ'),
+    (0x58, 'Τ'), (0x59, 'Υ'), (0x5a, 'Φ'), (0x5b, 'Χ'), (0x5c, 'Ψ'),
+    (0x5d, 'Ω'), (0x5e, 'Ϡ'), (0x61, 'α'), (0x62, 'β'), (0x63, 'ϐ'),
+    (0x64, 'γ'), (0x65, 'δ'), (0x66, 'ε'), (0x67, 'ϛ'), (0x68, 'ϝ'),
+    (0x69, 'ζ'), (0x6a, 'η'), (0x6b, 'θ'), (0x6c, 'ι'), (0x6d, 'κ'),
+    (0x6e, 'λ'), (0x6f, 'μ'), (0x70, 'ν'), (0x71, 'ξ'), (0x72, 'ο'),
+    (0x73, 'π'), (0x74, 'ϟ'), (0x75, 'ρ'), (0x76, 'σ'), (0x77, 'ς'),
+    (0x78, 'τ'), (0x79, 'υ'), (0x7a, 'φ'), (0x7b, 'χ'), (0x7c, 'ψ'),
+    (0x7d, 'ω'), (0x7e, 'ϡ'),
+];
+
+/// The combining marks of Basic Greek, with their bytes: grave, acute,
+/// diaeresis, circumflex, smooth and rough breathings, and iota subscript.
+const GREEK_DIACRITICS: [(u8, char); 7] = [
+    (0x21, '\u{300}'), (0x22, '\u{301}'), (0x23, '\u{308}'),
+    (0x24, '\u{342}'), (0x25, '\u{313}'), (0x26, '\u{314}'),
+    (0x27, '\u{345}'),
+];
+
+/// The Greek symbols set, with its bytes.
+const GREEK_SYMBOLS: [(u8, char); 3] = [(0x61, 'α'), (0x62, 'β'), (0x63, 'γ')];
+
+/// The subscripts set, with its bytes.
+const SUBSCRIPTS: [(u8, char); 14] = [
+    (0x28, '₍'), (0x29, '₎'), (0x2b, '₊'), (0x2d, '₋'), (0x30, '₀'),
+    (0x31, '₁'), (0x32, '₂'), (0x33, '₃'), (0x34, '₄'), (0x35, '₅'),
+    (0x36, '₆'), (0x37, '₇'), (0x38, '₈'), (0x39, '₉'),
+];
+
+/// The superscripts set, with its bytes.
+const SUPERSCRIPTS: [(u8, char); 14] = [
+    (0x28, '⁽'), (0x29, '⁾'), (0x2b, '⁺'), (0x2d, '⁻'), (0x30, '⁰'),
+    (0x31, '¹'), (0x32, '²'), (0x33, '³'), (0x34, '⁴'), (0x35, '⁵'),
+    (0x36, '⁶'), (0x37, '⁷'), (0x38, '⁸'), (0x39, '⁹'),
+];
+
+/// A character set that can be designated.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Set
+{
+    Ascii,
+    Ansel,
+    Cyrillic,
+    Greek,
+    GreekSymbols,
+    Subscripts,
+    Superscripts,
+    /// A single byte set that is not implemented.
+    Unsupported,
+    /// The 3-byte EACC set, which is not implemented.
+    Eacc
+}
+
+impl Set
+{
+    /// The escape sequence designating this set into G0, or `None` if the
+    /// set cannot be encoded.
+    fn designation(&self) -> Option<&'static [u8]>
+    {
+        match self
+        {
+            Set::Ascii => Some(b"\x1b(B"),
+            Set::Cyrillic => Some(b"\x1b(N"),
+            Set::Greek => Some(b"\x1b(S"),
+            Set::GreekSymbols => Some(b"\x1bg"),
+            Set::Subscripts => Some(b"\x1bb"),
+            Set::Superscripts => Some(b"\x1bp"),
+            _ => None
+        }
+    }
+
+    /// Decode a byte of this set, with the high bit cleared, if defined.
+    /// Return whether the character is a combining diacritic.
+    fn decode(&self, byte: u8) -> Option<(char, bool)>
+    {
+        let found = |table: &[(u8, char)]|
+            table.iter().find(|(b, _)| *b == byte).map(|(_, c)| *c);
+        let c = match self
+        {
+            Set::Ascii => Some(byte as char),
+            Set::Ansel => match byte | 0x80
+            {
+                b @ 0xa1..=0xc8 => Some(ANSEL[(b - 0xa1) as usize]),
+                b @ 0xe0..=0xfe =>
+                {
+                    let d = ANSEL_DIACRITICS[(b - 0xe0) as usize];
+                    return (d != '\0').then_some((d, true));
+                }
+                _ => None
+            },
+            Set::Cyrillic => match byte
+            {
+                0x40..=0x7e => Some(CYRILLIC[(byte - 0x40) as usize]),
+                _ => Some(byte as char)
+            },
+            Set::Greek => match found(&GREEK_DIACRITICS)
+            {
+                Some(d) => return Some((d, true)),
+                None => found(&GREEK)
+            },
+            Set::GreekSymbols => found(&GREEK_SYMBOLS),
+            Set::Subscripts => found(&SUBSCRIPTS),
+            Set::Superscripts => found(&SUPERSCRIPTS),
+            Set::Unsupported | Set::Eacc => None
+        };
+        c.filter(|&c| c != '\0').map(|c| (c, false))
+    }
+
+    /// Encode a character that is not ASCII into a G0 set and byte.
+    fn encode(c: char) -> Option<(Set, u8)>
+    {
+        let found = |set: Set, table: &[(u8, char)]|
+            table.iter().find(|(_, t)| *t == c).map(|(b, _)| (set, *b));
+        CYRILLIC.iter()
+            .position(|&t| t == c)
+            .map(|i| (Set::Cyrillic, 0x40 + i as u8))
+            .or_else(|| found(Set::Greek, &GREEK))
+            .or_else(|| found(Set::GreekSymbols, &GREEK_SYMBOLS))
+            .or_else(|| found(Set::Subscripts, &SUBSCRIPTS))
+            .or_else(|| found(Set::Superscripts, &SUPERSCRIPTS))
+    }
+}
+
+/// Parse the escape sequence starting after `ESC` in `bytes`, updating the
+/// designated sets. Return the length of the sequence, or `None` if it is not
+/// recognised.
+fn parse_escape(bytes: &[u8], g0: &mut Set, g1: &mut Set) -> Option<usize>
+{
+    let single = |f: u8| match f
+    {
+        b'B' => Set::Ascii,
+        b'E' => Set::Ansel,
+        b'N' => Set::Cyrillic,
+        b'S' => Set::Greek,
+        _ => Set::Unsupported
+    };
+    let (length, is_g1, set) = match bytes
+    {
+        [b'g', ..] => (1, false, Set::GreekSymbols),
+        [b'b', ..] => (1, false, Set::Subscripts),
+        [b'p', ..] => (1, false, Set::Superscripts),
+        [b's', ..] => (1, false, Set::Ascii),
+        [b'(' | b',', f, ..] => (2, false, single(*f)),
+        [b')' | b'-', f, ..] => (2, true, single(*f)),
+        [b'$', b'1', ..] => (2, false, Set::Eacc),
+        [b'$', b',', b'1', ..] => (3, false, Set::Eacc),
+        [b'$', b')' | b'-', b'1', ..] => (3, true, Set::Eacc),
+        _ => return None
+    };
+    if is_g1
+    {
+        *g1 = set;
+    }
+    else
+    {
+        *g0 = set;
+    }
+    Some(length)
+}
+
+pub struct Marc8Engine
+{
+    /// The character to decode undefined characters as.
+    pub replacement: char
+}
+
+/// Builder for a [`Marc8Engine`].
+pub struct Marc8EngineBuilder
+{
+    engine: Marc8Engine
+}
+
+impl Marc8Engine
+{
+    /// The default engine, decoding undefined characters as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Marc8Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Marc8EngineBuilder
+    {
+        Marc8EngineBuilder { engine: Marc8Engine::new() }
+    }
+
+    /// Decode, with undefined characters and dangling diacritics as
+    /// `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let mut decoded = String::new();
+        let mut g0 = Set::Ascii;
+        let mut g1 = Set::Ansel;
+        let mut diacritics = Vec::<char>::new();
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            let byte = bytes[i];
+            i += 1;
+            if byte == ESC
+                && let Some(length) =
+                    parse_escape(&bytes[i..], &mut g0, &mut g1)
+            {
+                i += length;
+                continue;
+            }
+            let set = if byte < 0x80 { g0 } else { g1 };
+            let (c, combining) = match byte & 0x7f
+            {
+                // Controls and space are not affected by designations
+                0x00..=0x20 | 0x7f if byte < 0x80 => (byte as char, false),
+                0x00..=0x20 | 0x7f => (replacement?, false),
+                _ if set == Set::Eacc =>
+                {
+                    // 3-byte characters
+                    i = bytes.len().min(i + 2);
+                    (replacement?, false)
+                }
+                b => match set.decode(b)
+                {
+                    Some(decoded) => decoded,
+                    None => (replacement?, false)
+                }
+            };
+            if combining
+            {
+                diacritics.push(c);
+            }
+            else
+            {
+                decoded.extend(std::iter::once(c)
+                    .chain(diacritics.drain(..))
+                    .nfc());
+            }
+        }
+        if !diacritics.is_empty()
+        {
+            decoded.push(replacement?);
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Default for Marc8Engine
+{
+    fn default() -> Self { Marc8Engine::new() }
+}
+
+impl Marc8EngineBuilder
+{
+    /// Set the character to decode undefined characters as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Marc8Engine { self.engine }
+}
+
+impl Engine for Marc8Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("MARC-8 / ANSEL", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+        let mut g0 = Set::Ascii;
+        let mut designate = |encoded: &mut Vec<u8>, set: Set|
+            {
+                if g0 != set
+                {
+                    encoded.extend_from_slice(set.designation().unwrap());
+                    g0 = set;
+                }
+            };
+
+        for c in string.chars()
+        {
+            if c.is_ascii_control() || c == ' '
+            {
+                encoded.push(c as u8);
+                continue;
+            }
+            if c.is_ascii()
+            {
+                designate(&mut encoded, Set::Ascii);
+                encoded.push(c as u8);
+                continue;
+            }
+            if let Some(i) = ANSEL.iter().position(|&a| a == c)
+            {
+                encoded.push(0xa1 + i as u8);
+                continue;
+            }
+            if let Some((set, byte)) = Set::encode(c)
+            {
+                designate(&mut encoded, set);
+                encoded.push(byte);
+                continue;
+            }
+            // Diacritics first, then the base letter
+            let mut decomposition = Vec::new();
+            decompose_canonical(c, |d| decomposition.push(d));
+            let (&base, diacritics) = decomposition.split_first()?;
+            if diacritics.is_empty()
+            {
+                return None;
+            }
+            if let Some((Set::Greek, byte)) = Set::encode(base)
+            {
+                // Greek letters take the combining marks of their own set
+                let marks: Option<Vec<u8>> = diacritics.iter()
+                    .map(|&d| GREEK_DIACRITICS.iter()
+                        .find(|(_, g)| *g == d)
+                        .map(|(b, _)| *b))
+                    .collect();
+                if let Some(marks) = marks
+                {
+                    designate(&mut encoded, Set::Greek);
+                    encoded.extend(marks);
+                    encoded.push(byte);
+                    continue;
+                }
+            }
+            for &d in diacritics
+            {
+                let i = ANSEL_DIACRITICS.iter().position(|&a| a == d)?;
+                encoded.push(0xe0 + i as u8);
+            }
+            if base.is_ascii()
+            {
+                designate(&mut encoded, Set::Ascii);
+                encoded.push(base as u8);
+            }
+            else if let Some(i) = ANSEL.iter().position(|&a| a == base)
+            {
+                encoded.push(0xa1 + i as u8);
+            }
+            else
+            {
+                let (set, byte) = Set::encode(base)?;
+                designate(&mut encoded, set);
+                encoded.push(byte);
+            }
+        }
+        designate(&mut encoded, Set::Ascii);
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        Marc8Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Marc8Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = Marc8Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xe2, 0x65]);
+
+        let encoded = engine.encode("Łódź").unwrap();
+        assert_eq!(encoded, &[0xa1, 0xe2, 0x6f, 0x64, 0xe2, 0x7a]);
+
+        let encoded = engine.encode("ǘǾ").unwrap();
+        // u, diaeresis, acute
+        assert_eq!(encoded, &[0xe8, 0xe2, 0x75, 0xe2, 0xa2]);
+
+        let encoded = engine.encode("Чехов A").unwrap();
+        assert_eq!(encoded,
+            b"\x1b(N\x7e\x45\x48\x4f\x57 \x1b(BA");
+
+        let encoded = engine.encode("H₂O").unwrap();
+        assert_eq!(encoded, b"H\x1bb2\x1b(BO");
+
+        let encoded = engine.encode("Ἀθῆναι").unwrap();
+        // Smooth breathing, Alpha, theta, circumflex, eta, nu, alpha, iota
+        assert_eq!(encoded,
+            b"\x1b(S\x25\x41\x6b\x24\x6a\x70\x61\x6c\x1b(B");
+
+        assert!(engine.encode("ミク").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = Marc8Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe1, 0x65]);
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(&[0xe8, 0xe2, 0x75, 0xb2]);
+        assert_eq!(decoded, "ǘø");
+
+        let decoded = engine.decode(b"\x1b(N\x7e\x45\x48\x4f\x57\x1bs!");
+        assert_eq!(decoded, "Чехов!");
+
+        let decoded = engine.decode(
+            b"\x1b(S\x4b\x22\x66\x6f\x6c\x77 \x26\x24\x27\x7d\x1bs!");
+        // Theta, acute, epsilon, mu, iota, final sigma, space, and rough
+        // breathing, circumflex, iota subscript, omega
+        assert_eq!(decoded, "Θέμις ᾧ!");
+
+        let decoded = engine.decode(b"\x1b$1\x21\x30\x21\x1b(BA");
+        // EACC characters are not supported
+        assert_eq!(decoded, "�A");
+
+        let decoded = engine.decode(&[0x61, 0xe2]);
+        assert_eq!(decoded, "a�");
+
+        let engine = Marc8Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "MARC-8 / ANSEL (errors as '?')");
+
+        let decoded = engine.decode(b"\xaf a\x1b$1\x21\x30\x21");
+        // Undefined byte, and EACC character
+        assert_eq!(decoded, "? a?");
+
+        let decoded = engine.decode(b"a\xe2");
+        assert_eq!(decoded, "a?");
+
+        assert!(engine.try_decode(b"a\xe2").is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Marc8Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
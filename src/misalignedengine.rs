@@ -0,0 +1,249 @@
+//! Deencoding engine reading the bytes of another engine out of alignment
+//!
+//! Encodings with multi-byte units, like UTF-16 or the
+//! [mixed UTF-8/UTF-16 schemes](crate::mixed816leengine), depend on reading
+//! the bytes in the right pairs. This engine wraps another engine, and breaks
+//! that in one of two ways:
+//! * the bytes of every pair are swapped, like `dd conv=swab` or a 16-bit
+//!   transport with the wrong byte order would do, in both directions;
+//! * a stray byte comes first, so that every unit is read as the second half of
+//!   one unit and the first half of the next. Encoding prepends a `0x00` byte,
+//!   and decoding starts one byte late: the first byte is then an orphan,
+//!   which can be dropped, or kept and decoded on its own unless it is that
+//!   `0x00` byte, so that decoding undoes encoding either way.
+//!
+//! Either way, encoding with this engine and decoding with the inner engine,
+//! or the other way round, gives the misaligned text. Decoding with this
+//! engine what it encoded gives the text back whenever the inner engine does,
+//! which the [mixed schemes](crate::mixed816leengine) do not for every scalar.
+
+use crate::engine::Engine;
+
+/// How the bytes are misaligned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Misalignment
+{
+    /// The bytes of every pair are swapped.
+    Swapped,
+    /// Decoding starts one byte late, and drops the first byte.
+    OddOffsetDrop,
+    /// Decoding starts one byte late, and decodes the first byte on its own,
+    /// unless it is the stray byte.
+    OddOffsetKeep
+}
+
+pub struct MisalignedEngine<'a, E: Engine + ?Sized>
+{
+    /// How the bytes are misaligned.
+    pub misalignment: Misalignment,
+    /// The inner engine.
+    pub engine: &'a E
+}
+
+/// The stray byte prepended when encoding one byte out of alignment.
+const STRAY: u8 = 0x00;
+
+/// Swap the bytes of every pair, leaving a trailing odd byte in place.
+fn swap(bytes: &[u8]) -> Vec<u8>
+{
+    let mut swapped = bytes.to_vec();
+    for pair in swapped.chunks_exact_mut(2)
+    {
+        pair.swap(0, 1);
+    }
+    swapped
+}
+
+/// Split the orphan first byte, if any, from the rest. The stray byte
+/// prepended at encoding is not an orphan, so that it decodes to nothing.
+fn split_orphan(bytes: &[u8]) -> (&[u8], &[u8])
+{
+    match bytes.split_first()
+    {
+        Some((&STRAY, rest)) => (&[], rest),
+        _ => bytes.split_at(bytes.len().min(1))
+    }
+}
+
+impl<E: Engine + ?Sized> Engine for MisalignedEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        let misalignment = match self.misalignment
+        {
+            Misalignment::Swapped => "byte-swapped",
+            Misalignment::OddOffsetDrop => "odd offset, orphan byte dropped",
+            Misalignment::OddOffsetKeep => "odd offset, orphan byte kept"
+        };
+        format!("{} ({})", self.engine.get_name(), misalignment)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let encoded = self.engine.encode(string)?;
+        match self.misalignment
+        {
+            Misalignment::Swapped => Some(swap(&encoded)),
+            _ => Some([&[STRAY], &encoded[..]].concat())
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let (orphan, rest) = split_orphan(bytes);
+        match self.misalignment
+        {
+            Misalignment::Swapped => self.engine.decode(&swap(bytes)),
+            Misalignment::OddOffsetDrop => self.engine.decode(rest),
+            Misalignment::OddOffsetKeep =>
+                self.engine.decode(orphan) + &self.engine.decode(rest)
+        }
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let (orphan, rest) = split_orphan(bytes);
+        match self.misalignment
+        {
+            Misalignment::Swapped => self.engine.try_decode(&swap(bytes)),
+            Misalignment::OddOffsetDrop => self.engine.try_decode(rest),
+            Misalignment::OddOffsetKeep => Some(self.engine.try_decode(orphan)?
+                + &self.engine.try_decode(rest)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::mixed816beengine::Mixed816BEEngine;
+    use crate::mixed816leengine::Mixed816LEEngine;
+    use crate::utf16engine::{Endianness, Utf16Engine};
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::Swapped, engine: &Utf8Engine::new()
+        };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"eHllo");
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::Swapped,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0x00, 0xe9]);
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetDrop,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0x00, 0xe9, 0x00]);
+
+        let encoded = engine.encode("Clé").unwrap();
+        assert_eq!(encoded, &[0x00, 0x43, 0x00, 0x6c, 0x00, 0xe9, 0x00]);
+
+        let decoded = Utf16Engine::new(Endianness::Little).decode(&encoded);
+        // 00 43 00 6c 00 e9 00
+        // => U+4300 U+6C00 U+E900, and a trailing byte
+        assert_eq!(decoded, "䌀氀\u{e900}�");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetDrop,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        let decoded = engine.decode(&[0x77, 0x00, 0x6f, 0x00, 0x72, 0x00]);
+        assert_eq!(decoded, "漀爀�");
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetKeep,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        let decoded = engine.decode(&[0x77, 0x00, 0x6f, 0x00, 0x72, 0x00]);
+        assert_eq!(decoded, "�漀爀�");
+
+        let decoded = engine.decode(&[0x00, 0x43, 0x00, 0x6c, 0x00]);
+        // The stray byte prepended at encoding decodes to nothing
+        assert_eq!(decoded, "Cl");
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetKeep,
+            engine: &Mixed816LEEngine::new()
+        };
+
+        let decoded = engine.decode(b"?Cl\xe9ment");
+        assert_eq!(decoded, "?Cl淩ent");
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::Swapped,
+            engine: &Mixed816LEEngine::new()
+        };
+
+        let decoded = engine.decode(&[0x43, 0x6c, 0x00, 0xe9]);
+        assert_eq!(decoded, "lCé");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::Swapped,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetDrop,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetKeep,
+            engine: &Utf16Engine::new(Endianness::Little)
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+
+        // The mixed schemes themselves only round trip for scalars whose
+        // first UTF-16 byte is not ASCII
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetDrop,
+            engine: &Mixed816BEEngine::new()
+        };
+
+        conformance::assert_round_trips(&engine, &["Hello", "Cl가ment"]);
+
+        let engine = MisalignedEngine {
+            misalignment: Misalignment::OddOffsetKeep,
+            engine: &Mixed816LEEngine::new()
+        };
+
+        conformance::assert_round_trips(&engine, &["Hello", "Clément"]);
+    }
+}
@@ -20,37 +20,37 @@
 //! could be what was on that insurance card (which I have since lost): encoding
 //! `"Clément"` as Latin-1 and then decoding it with this scheme gives
 //! `"Cl淩ent"`.
-use crate::engine::Engine;
+use crate::engine::{name_with_options, Engine};
 
-pub struct Mixed816LEEngine {}
+pub struct Mixed816LEEngine
+{
+    /// The character to decode invalid sequences as.
+    pub replacement: char
+}
 
-impl Engine for Mixed816LEEngine
+/// Builder for a [`Mixed816LEEngine`].
+pub struct Mixed816LEEngineBuilder
 {
-    fn get_name(&self) -> String { "mixed UTF-8/UTF-16LE".to_string() }
-    fn encode(&self, string: &str) -> Option<Vec<u8>>
-    {
-        let mut encoded = Vec::new();
+    engine: Mixed816LEEngine
+}
 
-        for c in string.chars()
-        {
-            if c.is_ascii()
-            {
-                encoded.push(c as u8);
-            }
-            else
-            {
-                let mut buf = [0u16; 2];
-                let _ = c.encode_utf16(&mut buf);
-                for i in 0..c.len_utf16()
-                {
-                    encoded.extend_from_slice(&buf[i].to_le_bytes());
-                }
-            }
-        }
+impl Mixed816LEEngine
+{
+    /// The default engine, decoding invalid sequences as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Mixed816LEEngine { replacement: char::REPLACEMENT_CHARACTER }
+    }
 
-        Some(encoded)
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Mixed816LEEngineBuilder
+    {
+        Mixed816LEEngineBuilder { engine: Mixed816LEEngine::new() }
     }
-    fn decode(&self, bytes: &[u8]) -> String
+
+    /// Decode, decoding invalid sequences as `replacement`, or failing without
+    /// one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
     {
         let mut decoded = String::new();
 
@@ -67,19 +67,19 @@ impl Engine for Mixed816LEEngine
                 if i + 1 == bytes.len()
                 {
                     // Cannot read a second byte
-                    decoded.push(char::REPLACEMENT_CHARACTER);
+                    decoded.push(replacement?);
                     i += 1;
                     continue;
                 }
                 let unit1 =
                     u16::from_le_bytes(bytes[i..i+2].try_into().unwrap());
-                if unit1 < 0xD800 || 0xE000 <= unit1
+                if !(0xD800..0xE000).contains(&unit1)
                 {
                     // Single unit, not a surrogate
-                    decoded.extend(
-                        char::decode_utf16([unit1])
+                    decoded.push_str(&char::decode_utf16([unit1])
                         // Iterator<Result<char, DecodeUtf16Error>>
-                        .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER)));
+                        .map(|r| r.ok().or(replacement))
+                        .collect::<Option<String>>()?);
                     i += 2;
                 }
                 else
@@ -88,22 +88,84 @@ impl Engine for Mixed816LEEngine
                     if bytes.len() <= i + 3
                     {
                         // Cannot read a second unit
-                        decoded.push(char::REPLACEMENT_CHARACTER);
+                        decoded.push(replacement?);
                         i += 2;
                         continue;
                     }
                     let unit2 = 
                         u16::from_le_bytes(bytes[i+2..i+4].try_into().unwrap());
-                    decoded.extend(
-                        char::decode_utf16([unit1, unit2])
+                    decoded.push_str(&char::decode_utf16([unit1, unit2])
                         // Iterator<Result<char, DecodeUtf16Error>>
-                        .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER)));
+                        .map(|r| r.ok().or(replacement))
+                        .collect::<Option<String>>()?);
                     i += 4;
                 }
             }
         }
 
-        decoded
+        Some(decoded)
+    }
+}
+
+impl Default for Mixed816LEEngine
+{
+    fn default() -> Self { Mixed816LEEngine::new() }
+}
+
+impl Mixed816LEEngineBuilder
+{
+    /// Set the character to decode invalid sequences as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Mixed816LEEngine { self.engine }
+}
+
+impl Engine for Mixed816LEEngine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("mixed UTF-8/UTF-16LE", &options)
+    }
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            if c.is_ascii()
+            {
+                encoded.push(c as u8);
+            }
+            else
+            {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf).iter()
+                {
+                    encoded.extend_from_slice(&unit.to_le_bytes());
+                }
+            }
+        }
+
+        Some(encoded)
+    }
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        Mixed816LEEngine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Mixed816LEEngine::decode_with(bytes, None)
     }
 }
 
@@ -111,9 +173,11 @@ impl Engine for Mixed816LEEngine
 mod tests {
     use super::*;
 
+    use crate::engine::conformance;
+
     #[test]
     fn encode() {
-        let engine = Mixed816LEEngine{};
+        let engine = Mixed816LEEngine::new();
 
         let encoded = engine.encode("Hello").unwrap();
         assert_eq!(encoded, b"Hello");
@@ -136,7 +200,7 @@ mod tests {
     #[test]
     fn decode()
     {
-        let engine = Mixed816LEEngine{};
+        let engine = Mixed816LEEngine::new();
 
         let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
         assert_eq!(decoded, "world!");
@@ -150,5 +214,25 @@ mod tests {
         // a4 20
         // => U+20A4
         assert_eq!(decoded, "₤");
+
+        let engine = Mixed816LEEngine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "mixed UTF-8/UTF-16LE (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0xe9]);
+        // Cannot read a second byte
+        assert_eq!(decoded, "A?");
+
+        assert!(engine.try_decode(&[0x41, 0xe9]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Mixed816LEEngine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_ascii_transparent(&engine);
     }
 }
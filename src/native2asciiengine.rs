@@ -0,0 +1,141 @@
+//! Deencoding engine for Java `native2ascii` / `.properties` escapes
+//!
+//! Encoding replaces every non-ASCII scalar with the `\uXXXX` escapes of its
+//! UTF-16 units, like the `native2ascii` tool does; nothing else is escaped.
+//!
+//! Decoding reads the bytes as ISO-8859-1, like `Properties.load()` does with
+//! an `InputStream`, then resolves `\uXXXX` escapes, like `native2ascii
+//! -reverse` does. Other backslashes are left untouched. Malformed escapes and
+//! unpaired surrogates become U+FFFD � REPLACEMENT CHARACTER; strict decoding
+//! fails on them instead, but accepts a genuine `\ufffd`.
+
+use crate::engine::Engine;
+use crate::jsonengine::{push_utf16_escapes, read_utf16_escape};
+
+pub struct Native2AsciiEngine {}
+
+impl Native2AsciiEngine
+{
+    /// Decode, with malformed escapes and unpaired surrogates as
+    /// `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let string: String = bytes.iter().map(|&b| b as char).collect();
+        let mut decoded = String::new();
+
+        let mut chars = string.chars().peekable();
+        while let Some(c) = chars.next()
+        {
+            if c == '\\' && chars.peek() == Some(&'u')
+            {
+                chars.next();
+                decoded.push(read_utf16_escape(&mut chars).or(replacement)?);
+            }
+            else
+            {
+                decoded.push(c);
+            }
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Engine for Native2AsciiEngine
+{
+    fn get_name(&self) -> String { "Java native2ascii".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = String::new();
+
+        for c in string.chars()
+        {
+            if c.is_ascii()
+            {
+                encoded.push(c);
+            }
+            else
+            {
+                push_utf16_escapes(&mut encoded, c);
+            }
+        }
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        Native2AsciiEngine::decode_with(bytes,
+            Some(char::REPLACEMENT_CHARACTER)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Native2AsciiEngine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = Native2AsciiEngine{};
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"\\u00e9");
+
+        let encoded = engine.encode("C:\\tmp").unwrap();
+        assert_eq!(encoded, b"C:\\tmp");
+
+        let encoded = engine.encode("😀").unwrap();
+        // U+1F600
+        // => U+D83D U+DE00
+        assert_eq!(encoded, b"\\ud83d\\ude00");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = Native2AsciiEngine{};
+
+        let decoded = engine.decode(b"world\\u0021");
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(b"Cl\\u00c3\\u00a9ment");
+        assert_eq!(decoded, "ClÃ©ment");
+
+        let decoded = engine.decode(b"C:\\tmp");
+        assert_eq!(decoded, "C:\\tmp");
+
+        let decoded = engine.decode(b"\\ud83d\\u00e9");
+        assert_eq!(decoded, "�é");
+
+        assert!(engine.try_decode(b"\\ud83d\\u00e9").is_none());
+
+        let decoded = engine.try_decode(b"\\ufffd");
+        assert_eq!(decoded.unwrap(), "�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Native2AsciiEngine{};
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
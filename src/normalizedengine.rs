@@ -0,0 +1,149 @@
+//! Deencoding engine adding a Unicode normalization step to another engine
+//!
+//! The same text can be represented by different sequences of scalars: `'é'`
+//! is U+00E9 in its composed form (NFC), but `'e'` followed by U+0301
+//! COMBINING ACUTE ACCENT in its decomposed form (NFD), which is for example
+//! what macOS uses for filenames. Both look the same, but are mangled very
+//! differently by later steps: decoding the UTF-8 encoding of `"Clément"` as
+//! Latin-1 gives `"ClÃ©ment"` in NFC, but `"CleÌ�ment"` in NFD.
+//!
+//! This engine wraps another engine: encoding normalizes the string before
+//! passing it to the inner engine, and decoding normalizes the output of the
+//! inner engine, like a system working in that form would. Normalization is
+//! performed with
+//! [`unicode-normalization`](https://crates.io/crates/unicode-normalization).
+
+use crate::engine::Engine;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// A Unicode normalization form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizationForm
+{
+    /// Canonical decomposition, followed by canonical composition.
+    NFC,
+    /// Canonical decomposition.
+    NFD,
+    /// Compatibility decomposition, followed by canonical composition.
+    NFKC,
+    /// Compatibility decomposition.
+    NFKD
+}
+
+impl NormalizationForm
+{
+    /// Normalize `string` to this form.
+    pub fn normalize(&self, string: &str) -> String
+    {
+        match self
+        {
+            NormalizationForm::NFC => string.nfc().collect(),
+            NormalizationForm::NFD => string.nfd().collect(),
+            NormalizationForm::NFKC => string.nfkc().collect(),
+            NormalizationForm::NFKD => string.nfkd().collect()
+        }
+    }
+}
+
+pub struct NormalizedEngine<'a, E: Engine + ?Sized>
+{
+    /// The normalization form.
+    pub form: NormalizationForm,
+    /// The inner engine.
+    pub engine: &'a E
+}
+
+impl<E: Engine + ?Sized> Engine for NormalizedEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        format!("{} ({:?})", self.engine.get_name(), self.form)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        self.engine.encode(&self.form.normalize(string))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        self.form.normalize(&self.engine.decode(bytes))
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Some(self.form.normalize(&self.engine.try_decode(bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let engine = NormalizedEngine {
+            form: NormalizationForm::NFD, engine: &Utf8Engine::new()
+        };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("é").unwrap();
+        // U+0065 U+0301
+        // => 65 cc 81
+        assert_eq!(encoded, &[0x65, 0xcc, 0x81]);
+
+        let engine = NormalizedEngine {
+            form: NormalizationForm::NFKC, engine: &Latin1Engine::new()
+        };
+
+        let encoded = engine.encode("e\u{301}ﬁ").unwrap();
+        assert_eq!(encoded, &[0xe9, 0x66, 0x69]);
+
+        let engine = NormalizedEngine {
+            form: NormalizationForm::NFD, engine: &Latin1Engine::new()
+        };
+
+        assert!(engine.encode("é").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = NormalizedEngine {
+            form: NormalizationForm::NFD, engine: &Latin1Engine::new()
+        };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "e\u{300}");
+
+        let engine = NormalizedEngine {
+            form: NormalizationForm::NFD, engine: &Utf8Engine::new()
+        };
+        let mangled =
+            Latin1Engine::new().decode(&engine.encode("Clément").unwrap());
+        assert_eq!(mangled, "CleÌ�ment");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = NormalizedEngine {
+            form: NormalizationForm::NFD, engine: &Utf8Engine::new()
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
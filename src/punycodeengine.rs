@@ -0,0 +1,264 @@
+//! Deencoding engine for Punycode
+//!
+//! Punycode ([RFC 3492](https://www.rfc-editor.org/rfc/rfc3492)) is the
+//! Bootstring encoding used for internationalized domain names: the ASCII
+//! characters of the string are kept in order, followed by a `-` delimiter and
+//! a base-36 encoding of the insertions of the other scalars, so `"Clément"`
+//! becomes `"Clment-cva"`.
+//!
+//! This engine encodes the whole string, without the `xn--` prefix; see
+//! [`idnaengine`](crate::idnaengine) for the label-wise variant. Bytes are read
+//! as UTF-8 before decoding, and input that is not valid Punycode decodes to a
+//! single U+FFFD � REPLACEMENT CHARACTER; strict decoding fails on it instead,
+//! but accepts the encoding of a genuine U+FFFD.
+
+use crate::engine::Engine;
+
+const BASE: u32 = 36;
+const TMIN: u32 = 1;
+const TMAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 0x80;
+
+pub struct PunycodeEngine {}
+
+/// Bias adaptation function (RFC 3492 §6.1).
+fn adapt(delta: u32, numpoints: u32, firsttime: bool) -> u32
+{
+    let mut delta = if firsttime { delta / DAMP } else { delta / 2 };
+    delta += delta / numpoints;
+    let mut k = 0;
+    while ((BASE - TMIN) * TMAX) / 2 < delta
+    {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    k + (BASE - TMIN + 1) * delta / (delta + SKEW)
+}
+
+/// The threshold for the digit at position `k`.
+fn threshold(k: u32, bias: u32) -> u32
+{
+    if k <= bias
+    {
+        TMIN
+    }
+    else if bias + TMAX <= k
+    {
+        TMAX
+    }
+    else
+    {
+        k - bias
+    }
+}
+
+fn encode_digit(d: u32) -> char
+{
+    if d < 26
+    {
+        (b'a' + d as u8) as char
+    }
+    else
+    {
+        (b'0' + (d - 26) as u8) as char
+    }
+}
+
+fn decode_digit(c: char) -> Option<u32>
+{
+    match c
+    {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 26),
+        _ => None
+    }
+}
+
+/// Encode `input` to Punycode (RFC 3492 §6.3).
+///
+/// Fail on arithmetic overflow, which only happens on absurdly long inputs.
+pub(crate) fn punycode_encode(input: &str) -> Option<String>
+{
+    let input: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut output: String = input.iter()
+        .filter(|&&c| c < 0x80)
+        .map(|&c| c as u8 as char)
+        .collect();
+
+    let basic = output.len() as u32;
+    let mut handled = basic;
+    if 0 < basic
+    {
+        output.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    while (handled as usize) < input.len()
+    {
+        // The next scalar to insert
+        let m = *input.iter().filter(|&&c| n <= c).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in input.iter()
+        {
+            if c < n
+            {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n
+            {
+                let mut q = delta;
+                let mut k = BASE;
+                loop
+                {
+                    let t = threshold(k, bias);
+                    if q < t
+                    {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(output)
+}
+
+/// Decode `input` from Punycode (RFC 3492 §6.2).
+///
+/// Fail on invalid input.
+pub(crate) fn punycode_decode(input: &str) -> Option<String>
+{
+    let (basic, extended) = match input.rfind('-')
+    {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => ("", input)
+    };
+    if !basic.is_ascii()
+    {
+        return None;
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.chars().peekable();
+    while digits.peek().is_some()
+    {
+        let oldi = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop
+        {
+            let digit = decode_digit(digits.next()?)?;
+            i = i.checked_add(digit.checked_mul(w)?)?;
+            let t = threshold(k, bias);
+            if digit < t
+            {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - oldi, length, oldi == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+impl Engine for PunycodeEngine
+{
+    fn get_name(&self) -> String { "Punycode".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        punycode_encode(string).map(String::into_bytes)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        punycode_decode(&String::from_utf8_lossy(bytes))
+            .unwrap_or(char::REPLACEMENT_CHARACTER.to_string())
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        punycode_decode(std::str::from_utf8(bytes).ok()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = PunycodeEngine{};
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello-");
+
+        let encoded = engine.encode("Clément").unwrap();
+        assert_eq!(encoded, b"Clment-cva");
+
+        let encoded = engine.encode("ミク").unwrap();
+        assert_eq!(encoded, b"pck1e");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = PunycodeEngine{};
+
+        let decoded = engine.decode(b"world!-");
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"bcher-kva");
+        assert_eq!(decoded, "bücher");
+
+        let decoded = engine.decode(b"pck1e");
+        assert_eq!(decoded, "ミク");
+
+        let decoded = engine.decode(b"abc-!");
+        assert_eq!(decoded, "�");
+
+        assert!(engine.try_decode(b"abc-!").is_none());
+
+        let encoded = engine.encode("\u{fffd}").unwrap();
+        assert_eq!(engine.try_decode(&encoded).unwrap(), "�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = PunycodeEngine{};
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
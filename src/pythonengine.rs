@@ -0,0 +1,191 @@
+//! Deencoding engine for Python's `unicode_escape` codec
+//!
+//! This is the escaping of Python string reprs: encoding writes scalars up to
+//! U+00FF outside of printable ASCII as `\xNN`, the rest of the BMP as
+//! `\uXXXX`, and other scalars as `\UXXXXXXXX`, so `"Clément"` becomes
+//! `"Cl\xe9ment"`. Backslashes are escaped as `\\`, and the usual `\n`, `\r`
+//! and `\t` are used.
+//!
+//! Decoding reads the bytes as ISO-8859-1, like Python does, then resolves
+//! escapes. Unknown escapes are kept as-is, and malformed ones, as well as
+//! escaped surrogates, become U+FFFD � REPLACEMENT CHARACTER; strict decoding
+//! fails on them instead, but accepts a genuine `\ufffd`.
+
+use crate::engine::Engine;
+use crate::jsonengine::read_hex;
+
+pub struct PythonEngine {}
+
+impl PythonEngine
+{
+    /// Decode, with malformed escapes and escaped surrogates as `replacement`,
+    /// or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let string: String = bytes.iter().map(|&b| b as char).collect();
+        let mut decoded = String::new();
+
+        let mut chars = string.chars().peekable();
+        while let Some(c) = chars.next()
+        {
+            if c != '\\'
+            {
+                decoded.push(c);
+                continue;
+            }
+            let escaped = match chars.next()
+            {
+                // Line continuation
+                Some('\n') => continue,
+                Some('a') => '\u{07}',
+                Some('b') => '\u{08}',
+                Some('f') => '\u{0c}',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('v') => '\u{0b}',
+                Some(e @ ('\\' | '\'' | '"')) => e,
+                Some(d @ '0'..='7') =>
+                {
+                    let mut value = d as u32 - '0' as u32;
+                    for _ in 0..2
+                    {
+                        let Some(d) = chars.peek().and_then(|d| d.to_digit(8))
+                        else
+                        {
+                            break;
+                        };
+                        value = value * 8 + d;
+                        chars.next();
+                    }
+                    // At most 0o777
+                    char::from_u32(value).unwrap()
+                }
+                Some(x @ ('x' | 'u' | 'U')) =>
+                {
+                    let digits = match x
+                    {
+                        'x' => 2,
+                        'u' => 4,
+                        _ => 8
+                    };
+                    read_hex(&mut chars, digits)
+                        .and_then(char::from_u32)
+                        .or(replacement)?
+                }
+                Some(other) =>
+                {
+                    decoded.push('\\');
+                    other
+                }
+                None => '\\'
+            };
+            decoded.push(escaped);
+        }
+
+        Some(decoded)
+    }
+}
+
+
+impl Engine for PythonEngine
+{
+    fn get_name(&self) -> String { "Python unicode_escape".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = String::new();
+
+        for c in string.chars()
+        {
+            match c
+            {
+                '\\' => encoded.push_str("\\\\"),
+                '\n' => encoded.push_str("\\n"),
+                '\r' => encoded.push_str("\\r"),
+                '\t' => encoded.push_str("\\t"),
+                ' '..='~' => encoded.push(c),
+                '\0'..='\u{ff}' =>
+                    encoded.push_str(&format!("\\x{:02x}", c as u32)),
+                '\u{100}'..='\u{ffff}' =>
+                    encoded.push_str(&format!("\\u{:04x}", c as u32)),
+                _ => encoded.push_str(&format!("\\U{:08x}", c as u32))
+            }
+        }
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        PythonEngine::decode_with(bytes, Some(char::REPLACEMENT_CHARACTER))
+            .unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        PythonEngine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = PythonEngine{};
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, b"\\xe9");
+
+        let encoded = engine.encode("€").unwrap();
+        assert_eq!(encoded, b"\\u20ac");
+
+        let encoded = engine.encode("😀").unwrap();
+        assert_eq!(encoded, b"\\U0001f600");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = PythonEngine{};
+
+        let decoded = engine.decode(b"world\\x21");
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"Cl\\xc3\\xa9ment");
+        assert_eq!(decoded, "ClÃ©ment");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "è");
+
+        let decoded = engine.decode(b"\\u20a4\\q");
+        assert_eq!(decoded, "₤\\q");
+
+        let decoded = engine.decode(b"\\ud83d");
+        assert_eq!(decoded, "�");
+
+        assert!(engine.try_decode(b"\\ud83d").is_none());
+
+        let decoded = engine.try_decode(b"\\ufffd");
+        assert_eq!(decoded.unwrap(), "�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = PythonEngine{};
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -0,0 +1,168 @@
+//! Deencoding engine for ASCII-only systems showing `?` for everything else
+//!
+//! The last step of many pipelines is a terminal, a printer or a database
+//! column that only knows ASCII, and shows a `'?'` for anything else. The
+//! number of `'?'` then tells how the text was encoded before: `"Clément"`
+//! becomes `"Cl??ment"` if `'é'` was sent as two bytes of UTF-8 to a system
+//! replacing each byte, but `"Cl?ment"` if it was sent as Latin-1, or to a
+//! system replacing each character.
+//!
+//! This engine wraps the engine used before the replacement. Decoding either
+//! replaces every non-ASCII byte before passing the bytes to the inner engine,
+//! or replaces every non-ASCII character the inner engine gives, including
+//! U+FFFD � REPLACEMENT CHARACTER. Encoding uses the inner engine, and fails on
+//! non-ASCII strings, since those never come out of such a system.
+
+use crate::engine::Engine;
+
+/// What is replaced with a `'?'`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replacement
+{
+    /// Every non-ASCII byte.
+    Byte,
+    /// Every non-ASCII character.
+    Character
+}
+
+pub struct QuestionMarkEngine<'a, E: Engine + ?Sized>
+{
+    /// What is replaced with a `'?'`.
+    pub replacement: Replacement,
+    /// The inner engine.
+    pub engine: &'a E
+}
+
+impl<E: Engine + ?Sized> Engine for QuestionMarkEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        let replacement = match self.replacement
+        {
+            Replacement::Byte => "byte",
+            Replacement::Character => "character"
+        };
+        format!("{} (? for each non-ASCII {})", self.engine.get_name(),
+            replacement)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        if string.is_ascii()
+        {
+            self.engine.encode(string)
+        }
+        else
+        {
+            None
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        match self.replacement
+        {
+            Replacement::Byte =>
+            {
+                let bytes: Vec<u8> = bytes.iter()
+                    .map(|&b| if b.is_ascii() { b } else { b'?' })
+                    .collect();
+                self.engine.decode(&bytes)
+            },
+            Replacement::Character => self.engine.decode(bytes)
+                .chars()
+                .map(|c| if c.is_ascii() { c } else { '?' })
+                .collect()
+        }
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        match self.replacement
+        {
+            Replacement::Byte =>
+            {
+                let bytes: Vec<u8> = bytes.iter()
+                    .map(|&b| if b.is_ascii() { b } else { b'?' })
+                    .collect();
+                self.engine.try_decode(&bytes)
+            },
+            Replacement::Character => Some(self.engine.try_decode(bytes)?
+                .chars()
+                .map(|c| if c.is_ascii() { c } else { '?' })
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let engine = QuestionMarkEngine {
+            replacement: Replacement::Byte, engine: &Utf8Engine::new()
+        };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("Cl??ment").unwrap();
+        assert_eq!(encoded, b"Cl??ment");
+
+        assert!(engine.encode("Clément").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = QuestionMarkEngine {
+            replacement: Replacement::Byte, engine: &Utf8Engine::new()
+        };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"Cl\xc3\xa9ment");
+        assert_eq!(decoded, "Cl??ment");
+
+        let decoded = engine.decode(b"Cl\xe9ment");
+        assert_eq!(decoded, "Cl?ment");
+
+        let engine = QuestionMarkEngine {
+            replacement: Replacement::Character, engine: &Utf8Engine::new()
+        };
+
+        let decoded = engine.decode(b"Cl\xc3\xa9ment");
+        assert_eq!(decoded, "Cl?ment");
+
+        let decoded = engine.decode(b"\xf0\x9f\x98\x80 \xe9");
+        assert_eq!(decoded, "? ?");
+
+        let engine = QuestionMarkEngine {
+            replacement: Replacement::Character, engine: &Latin1Engine::new()
+        };
+
+        let decoded = engine.decode(b"Cl\xc3\xa9ment");
+        assert_eq!(decoded, "Cl??ment");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = QuestionMarkEngine {
+            replacement: Replacement::Character, engine: &Utf8Engine::new()
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
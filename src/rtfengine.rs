@@ -0,0 +1,420 @@
+//! Deencoding engine for RTF text escapes
+//!
+//! RTF is a 7-bit format: characters of the document's ANSI code page, as set
+//! by `\ansicpg`, are written as `\'hh` escapes of their bytes, and other
+//! characters as `\uN` escapes of their UTF-16 units, with `N` a signed 16-bit
+//! number, followed by a fallback for readers that do not know `\uN`.
+//! Mishandling those gives `"Cl\'e9ment"`, `"Cl?ment"`, or the wrong
+//! character if the code page is not the expected one.
+//!
+//! This engine wraps the engine of the ANSI code page. Encoding escapes `\`,
+//! `{` and `}`, writes characters of the code page as `\'hh`, and others as
+//! `\uN?`, with `'?'` as fallback. Decoding reads text, ignoring group braces
+//! and unknown control words: `\'hh` escapes and raw 8-bit bytes are decoded
+//! with the code page engine, and `\uN` skips the number of fallback
+//! characters set by `\ucN` (1 by default) in the current group. Destinations
+//! that are not text, such as the font table or `\*` ones, are skipped with
+//! their group. Invalid escapes are decoded as U+FFFD � REPLACEMENT CHARACTER.
+//!
+//! Strict decoding is conservative: it fails on any U+FFFD in the output, so
+//! text holding a genuine U+FFFD, such as `\u-3?`, is pruned along with invalid
+//! text.
+
+use crate::engine::Engine;
+
+/// Destinations whose group holds no text of the document.
+const SKIPPED_DESTINATIONS: &[&[u8]] = &[
+    b"colortbl", b"fonttbl", b"info", b"pict", b"stylesheet",
+];
+
+pub struct RtfEngine<'a, E: Engine + ?Sized>
+{
+    /// The engine of the ANSI code page.
+    pub engine: &'a E
+}
+
+/// Decoded text, with pending bytes and UTF-16 units.
+struct Output<'a, E: Engine + ?Sized>
+{
+    engine: &'a E,
+    decoded: String,
+    bytes: Vec<u8>,
+    units: Vec<u16>
+}
+
+impl<E: Engine + ?Sized> Output<'_, E>
+{
+    /// Decode the pending bytes and units.
+    fn flush(&mut self)
+    {
+        if !self.bytes.is_empty()
+        {
+            self.decoded.push_str(&self.engine.decode(&self.bytes));
+            self.bytes.clear();
+        }
+        if !self.units.is_empty()
+        {
+            self.decoded.push_str(&String::from_utf16_lossy(&self.units));
+            self.units.clear();
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8)
+    {
+        if !self.units.is_empty()
+        {
+            self.flush();
+        }
+        self.bytes.push(byte);
+    }
+
+    fn push_unit(&mut self, unit: u16)
+    {
+        if !self.bytes.is_empty()
+        {
+            self.flush();
+        }
+        self.units.push(unit);
+    }
+
+    fn push_char(&mut self, c: char)
+    {
+        self.flush();
+        self.decoded.push(c);
+    }
+}
+
+/// Parse a control word at the start of `bytes`, after the backslash. Return
+/// the word, its parameter, and its length with the delimiting space.
+fn parse_control_word(bytes: &[u8]) -> (&[u8], Option<i32>, usize)
+{
+    let letters = bytes.iter()
+        .take_while(|b| b.is_ascii_alphabetic())
+        .count();
+    let mut length = letters;
+    let negative = bytes.get(length) == Some(&b'-');
+    if negative
+    {
+        length += 1;
+    }
+    let digits = bytes[length..].iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    let parameter = std::str::from_utf8(&bytes[length..length + digits]).ok()
+        .and_then(|digits| digits.parse::<i32>().ok())
+        .map(|p| if negative { -p } else { p });
+    if digits == 0 && negative
+    {
+        // Not a parameter
+        length -= 1;
+    }
+    length += digits;
+    if bytes.get(length) == Some(&b' ')
+    {
+        length += 1;
+    }
+    (&bytes[..letters], parameter, length)
+}
+
+/// The length of the character at the start of `bytes`, for skipping fallback
+/// characters after `\uN`. Groups end the fallback.
+fn fallback_length(bytes: &[u8]) -> Option<usize>
+{
+    match bytes
+    {
+        [b'{' | b'}', ..] | [] => None,
+        [b'\\', b'\'', ..] => Some(bytes.len().min(4)),
+        [b'\\', next, ..] if next.is_ascii_alphabetic() =>
+            Some(1 + parse_control_word(&bytes[1..]).2),
+        [b'\\', _, ..] => Some(2),
+        _ => Some(1)
+    }
+}
+
+/// The length of the group at the start of `bytes`, up to its closing brace
+/// or the end, if it is a destination that is not text.
+fn skipped_group_length(bytes: &[u8]) -> Option<usize>
+{
+    let skipped = match bytes
+    {
+        [b'{', b'\\', b'*', ..] => true,
+        [b'{', b'\\', rest @ ..] =>
+            SKIPPED_DESTINATIONS.contains(&parse_control_word(rest).0),
+        _ => false
+    };
+    if !skipped
+    {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len()
+    {
+        match bytes[i]
+        {
+            b'\\' => i += 1,
+            b'{' => depth += 1,
+            b'}' =>
+            {
+                depth -= 1;
+                if depth == 0
+                {
+                    return Some(i + 1);
+                }
+            },
+            _ => ()
+        }
+        i += 1;
+    }
+    Some(bytes.len())
+}
+
+impl<E: Engine + ?Sized> Engine for RtfEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        format!("RTF escapes ({})", self.engine.get_name())
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = String::new();
+
+        for c in string.chars()
+        {
+            match c
+            {
+                '\\' | '{' | '}' =>
+                {
+                    encoded.push('\\');
+                    encoded.push(c);
+                },
+                '\n' => encoded.push_str("\\par "),
+                '\t' => encoded.push_str("\\tab "),
+                // Readers ignore line breaks, and may ignore other controls
+                _ if c.is_ascii_control() =>
+                    encoded.push_str(&format!("\\'{:02x}", c as u8)),
+                _ if c.is_ascii() => encoded.push(c),
+                _ =>
+                {
+                    let mut buf = [0u8; 4];
+                    match self.engine.encode(c.encode_utf8(&mut buf))
+                    {
+                        Some(bytes) => for byte in bytes
+                        {
+                            encoded.push_str(&format!("\\'{:02x}", byte));
+                        },
+                        None => for unit in c.encode_utf16(&mut [0u16; 2])
+                        {
+                            encoded.push_str(&format!("\\u{}?", *unit as i16));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let mut output = Output {
+            engine: self.engine,
+            decoded: String::new(),
+            bytes: Vec::new(),
+            units: Vec::new()
+        };
+        // Number of fallback characters after `\uN`
+        let mut fallback = 1;
+        // Numbers of fallback characters of the enclosing groups
+        let mut groups = Vec::new();
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            match &bytes[i..]
+            {
+                [b'\\', b'\'', rest @ ..] =>
+                {
+                    let byte = rest.get(..2)
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                    match byte
+                    {
+                        Some(byte) =>
+                        {
+                            output.push_byte(byte);
+                            i += 4;
+                        },
+                        None =>
+                        {
+                            output.push_char(char::REPLACEMENT_CHARACTER);
+                            i += 2;
+                        }
+                    }
+                },
+                [b'\\', symbol @ (b'\\' | b'{' | b'}'), ..] =>
+                {
+                    output.push_char(*symbol as char);
+                    i += 2;
+                },
+                [b'\\', next, ..] if next.is_ascii_alphabetic() =>
+                {
+                    let (word, parameter, length) =
+                        parse_control_word(&bytes[i + 1..]);
+                    i += 1 + length;
+                    match (word, parameter)
+                    {
+                        (b"u", Some(n)) =>
+                        {
+                            output.push_unit(n as u16);
+                            for _ in 0..fallback
+                            {
+                                match fallback_length(&bytes[i..])
+                                {
+                                    Some(length) => i += length,
+                                    None => break
+                                }
+                            }
+                        },
+                        (b"uc", Some(n)) => fallback = n.max(0),
+                        (b"par" | b"line", _) => output.push_char('\n'),
+                        (b"tab", _) => output.push_char('\t'),
+                        (b"emdash", _) => output.push_char('—'),
+                        (b"endash", _) => output.push_char('–'),
+                        (b"lquote", _) => output.push_char('‘'),
+                        (b"rquote", _) => output.push_char('’'),
+                        (b"ldblquote", _) => output.push_char('“'),
+                        (b"rdblquote", _) => output.push_char('”'),
+                        (b"bullet", _) => output.push_char('•'),
+                        // Unknown, or not text
+                        _ => ()
+                    }
+                },
+                [b'\\', symbol, ..] =>
+                {
+                    match symbol
+                    {
+                        b'~' => output.push_char('\u{a0}'),
+                        b'-' => output.push_char('\u{ad}'),
+                        b'_' => output.push_char('\u{2011}'),
+                        // Unknown, or not text
+                        _ => ()
+                    }
+                    i += 2;
+                },
+                [b'\\'] =>
+                {
+                    output.push_char(char::REPLACEMENT_CHARACTER);
+                    i += 1;
+                },
+                [b'{', ..] =>
+                {
+                    match skipped_group_length(&bytes[i..])
+                    {
+                        Some(length) => i += length,
+                        None =>
+                        {
+                            groups.push(fallback);
+                            i += 1;
+                        }
+                    }
+                },
+                [b'}', ..] =>
+                {
+                    fallback = groups.pop().unwrap_or(fallback);
+                    i += 1;
+                },
+                // Line breaks of the file
+                [b'\r' | b'\n', ..] => i += 1,
+                [byte, ..] if byte.is_ascii() =>
+                {
+                    output.push_char(*byte as char);
+                    i += 1;
+                },
+                [byte, ..] =>
+                {
+                    output.push_byte(*byte);
+                    i += 1;
+                },
+                [] => unreachable!()
+            }
+        }
+
+        output.flush();
+        output.decoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::latin2engine::Latin2Engine;
+
+    #[test]
+    fn encode() {
+        let engine = RtfEngine { engine: &Latin1Engine::new() };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("Clément").unwrap();
+        assert_eq!(encoded, b"Cl\\'e9ment");
+
+        let encoded = engine.encode("{ミク}😀\n").unwrap();
+        assert_eq!(encoded,
+            b"\\{\\u12511?\\u12463?\\}\\u-10179?\\u-8704?\\par ");
+
+        let engine = RtfEngine { engine: &Latin2Engine::new() };
+
+        let encoded = engine.encode("Łódź").unwrap();
+        assert_eq!(encoded, b"\\'a3\\'f3d\\'9f");
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = RtfEngine { engine: &Latin1Engine::new() };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"{\\rtf1\\ansi\\ansicpg1252 \
+            Cl\\'e9ment\\par\nCl\\u233\\'e9ment \\uc0\\u233 ment}");
+        assert_eq!(decoded, "Clément\nClément ément");
+
+        let decoded = engine.decode(b"\\uc2\\u-10179\\'3f\\'3f\\u-8704??!");
+        assert_eq!(decoded, "😀!");
+
+        let decoded = engine.decode(b"\\'zz\\");
+        assert_eq!(decoded, "�zz�");
+
+        let decoded = engine.decode(b"{\\rtf1{\\fonttbl{\\f0 Arial;}}\
+            {\\colortbl;\\red0\\green0\\blue0;}{\\*\\generator x\\{;}Hello}");
+        assert_eq!(decoded, "Hello");
+
+        let decoded = engine.decode(b"{\\uc0{\\uc2 \\u233??}\\u233 }\\u233?");
+        // `\ucN` only lasts until the end of its group
+        assert_eq!(decoded, "ééé");
+
+        let engine = RtfEngine { engine: &Latin2Engine::new() };
+
+        let decoded = engine.decode(b"\\'a3\\'f3d\\'9f");
+        assert_eq!(decoded, "Łódź");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = RtfEngine { engine: &Latin1Engine::new() };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -0,0 +1,575 @@
+//! Deencoding engine for SCSU
+//!
+//! The Standard Compression Scheme for Unicode
+//! ([UTS #6](https://www.unicode.org/reports/tr6/)) is used by a few databases
+//! and exports, such as SAP systems or Lotus Notes. It is a stateful encoding:
+//! * in single-byte mode, ASCII is written as is, and `0x80` to `0xFF` index
+//!   one of eight dynamic windows of 128 scalars. Tag bytes in the C0 range
+//!   switch windows, redefine them, or quote a single character;
+//! * in Unicode mode, text is written as UTF-16BE, with tag bytes in `0xE0` to
+//!   `0xF2` to go back to single-byte mode.
+//!
+//! Misread as Latin-1, SCSU text is mostly ASCII, with letters replaced by
+//! upper half characters and stray control codes.
+//!
+//! Decoding supports the whole scheme, and replaces reserved tags and
+//! truncated sequences with U+FFFD � REPLACEMENT CHARACTER, or another
+//! character configured with [`ScsuEngine::builder()`]. Encoding produces
+//! valid, but not optimal, SCSU: it switches to an existing window when it
+//! can, defines windows as needed, and only uses Unicode mode for runs of
+//! characters that no window can hold, such as CJK ideographs.
+//!
+//! Strict decoding fails on reserved tags and truncated sequences, but accepts
+//! a genuine U+FFFD.
+
+use crate::engine::{name_with_options, Engine};
+
+pub struct ScsuEngine
+{
+    /// The character to decode invalid sequences as.
+    pub replacement: char
+}
+
+/// Builder for a [`ScsuEngine`].
+pub struct ScsuEngineBuilder
+{
+    engine: ScsuEngine
+}
+
+/// The offsets of the static windows.
+const STATIC_WINDOWS: [u32; 8] = [0x0000, 0x0080, 0x0100, 0x0300, 0x2000,
+    0x2080, 0x2100, 0x3000];
+
+/// The initial offsets of the dynamic windows.
+const DYNAMIC_WINDOWS: [u32; 8] = [0x0080, 0x00c0, 0x0400, 0x0600, 0x0900,
+    0x3040, 0x30a0, 0xff00];
+
+// Single-byte mode tags
+const SQ0: u8 = 0x01;
+const SDX: u8 = 0x0b;
+const SQU: u8 = 0x0e;
+const SCU: u8 = 0x0f;
+const SC0: u8 = 0x10;
+const SD0: u8 = 0x18;
+
+// Unicode mode tags
+const UC0: u8 = 0xe0;
+const UD0: u8 = 0xe8;
+const UQU: u8 = 0xf0;
+const UDX: u8 = 0xf1;
+
+/// The offset of a window, from the byte defining it.
+fn window_offset(byte: u8) -> Option<u32>
+{
+    match byte
+    {
+        0x01..=0x67 => Some(byte as u32 * 0x80),
+        0x68..=0xa7 => Some(byte as u32 * 0x80 + 0xac00),
+        0xf9 => Some(0x00c0),
+        0xfa => Some(0x0250),
+        0xfb => Some(0x0370),
+        0xfc => Some(0x0530),
+        0xfd => Some(0x3040),
+        0xfe => Some(0x30a0),
+        0xff => Some(0xff60),
+        _ => None
+    }
+}
+
+/// The byte defining a window that holds the BMP scalar `scalar`, if any.
+fn window_byte(scalar: u32) -> Option<u8>
+{
+    match scalar
+    {
+        0x0250..=0x02af => Some(0xfa),
+        0x0370..=0x03ef => Some(0xfb),
+        0x0530..=0x05af => Some(0xfc),
+        0x3040..=0x30bf => Some(0xfd),
+        0x30c0..=0x311f => Some(0xfe),
+        0xff60..=0xffdf => Some(0xff),
+        0x0080..=0x33ff => Some((scalar / 0x80) as u8),
+        0xe000..=0xffff => Some(((scalar - 0xac00) / 0x80) as u8),
+        _ => None
+    }
+}
+
+/// Whether `scalar` is written as is in single-byte mode.
+fn is_literal(scalar: u32) -> bool
+{
+    matches!(scalar, 0x00 | 0x09 | 0x0a | 0x0d | 0x20..=0x7f)
+}
+
+/// Whether `c` can only be written in Unicode mode, or quoted.
+fn needs_unicode(c: char) -> bool
+{
+    let scalar = c as u32;
+    scalar < 0x10000 && !is_literal(scalar) && scalar >= 0x20
+        && window_byte(scalar).is_none()
+}
+
+/// The state of an encoder or decoder.
+struct State
+{
+    /// Whether in Unicode mode.
+    unicode: bool,
+    /// The offsets of the dynamic windows.
+    windows: [u32; 8],
+    /// The active dynamic window.
+    active: usize,
+    /// The next dynamic window to redefine, when encoding.
+    next: usize
+}
+
+impl State
+{
+    fn new() -> State
+    {
+        State { unicode: false, windows: DYNAMIC_WINDOWS, active: 0, next: 0 }
+    }
+
+    /// The dynamic window holding `scalar`, if any.
+    fn find_window(&self, scalar: u32) -> Option<usize>
+    {
+        self.windows.iter()
+            .position(|&w| (w..w + 0x80).contains(&scalar))
+    }
+
+    /// Define and select a window above the BMP, from the arguments of
+    /// `SDX` or `UDX`.
+    fn define_extended(&mut self, high: u8, low: u8)
+    {
+        let n = (high >> 5) as usize;
+        let index = ((high as u32 & 0x1f) << 8) | low as u32;
+        self.windows[n] = 0x10000 + (index << 7);
+        self.active = n;
+    }
+
+    /// Pick the next dynamic window to redefine.
+    fn take_next(&mut self) -> usize
+    {
+        let n = self.next;
+        self.next = (self.next + 1) % 8;
+        n
+    }
+
+    /// Encode `c` in single-byte mode.
+    fn encode_single_byte(&mut self, c: char, next: Option<char>,
+        encoded: &mut Vec<u8>)
+    {
+        let scalar = c as u32;
+        if is_literal(scalar)
+        {
+            encoded.push(scalar as u8);
+        }
+        else if scalar < 0x20
+        {
+            // Quote from static window 0, not to be taken for a tag
+            encoded.extend([SQ0, scalar as u8]);
+        }
+        else if let Some(n) = self.find_window(scalar)
+        {
+            if n != self.active
+            {
+                encoded.push(SC0 + n as u8);
+                self.active = n;
+            }
+            encoded.push((0x80 + scalar - self.windows[n]) as u8);
+        }
+        else if scalar >= 0x10000
+        {
+            let n = self.take_next();
+            let index = (scalar - 0x10000) >> 7;
+            encoded.extend([SDX, ((n as u32) << 5 | index >> 8) as u8,
+                index as u8]);
+            self.windows[n] = 0x10000 + (index << 7);
+            self.active = n;
+            encoded.push((0x80 + scalar - self.windows[n]) as u8);
+        }
+        else if let Some(byte) = window_byte(scalar)
+        {
+            let n = self.take_next();
+            encoded.extend([SD0 + n as u8, byte]);
+            self.windows[n] = window_offset(byte).unwrap();
+            self.active = n;
+            encoded.push((0x80 + scalar - self.windows[n]) as u8);
+        }
+        else if next.is_some_and(needs_unicode)
+        {
+            encoded.push(SCU);
+            self.unicode = true;
+            self.encode_unicode(c, next, encoded);
+        }
+        else
+        {
+            encoded.push(SQU);
+            encoded.extend((scalar as u16).to_be_bytes());
+        }
+    }
+
+    /// Encode `c` in Unicode mode.
+    fn encode_unicode(&mut self, c: char, next: Option<char>,
+        encoded: &mut Vec<u8>)
+    {
+        if !needs_unicode(c) && (c as u32) < 0x10000
+        {
+            encoded.push(UC0 + self.active as u8);
+            self.unicode = false;
+            self.encode_single_byte(c, next, encoded);
+            return;
+        }
+        let mut buf = [0u16; 2];
+        for unit in c.encode_utf16(&mut buf).iter()
+        {
+            if (0xe0..=0xf2).contains(&(unit >> 8))
+            {
+                // Not to be taken for a tag
+                encoded.push(UQU);
+            }
+            encoded.extend(unit.to_be_bytes());
+        }
+    }
+}
+
+/// Push `scalar` to `units` as UTF-16, or `None` if it is not a scalar.
+fn push_scalar(units: &mut Vec<Option<u16>>, scalar: u32)
+{
+    match char::from_u32(scalar)
+    {
+        Some(c) => units.extend(c.encode_utf16(&mut [0u16; 2]).iter()
+            .map(|&unit| Some(unit))),
+        None => units.push(None)
+    }
+}
+
+impl ScsuEngine
+{
+    /// The default engine, decoding invalid sequences as U+FFFD.
+    pub const fn new() -> Self
+    {
+        ScsuEngine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> ScsuEngineBuilder
+    {
+        ScsuEngineBuilder { engine: ScsuEngine::new() }
+    }
+
+    /// Decode, with invalid sequences as `replacement`, or failing without
+    /// one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        // UTF-16 units, since quoted units may be surrogates, and `None` for
+        // invalid sequences
+        let mut units = Vec::new();
+        let mut state = State::new();
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            let b = bytes[i];
+            i += 1;
+            // Arguments of the tag
+            let argument = |n: usize| bytes.get(i..i + n);
+            // Window offset argument of `SDn` and `UDn`
+            let offset = || argument(1).map(|a| window_offset(a[0]));
+
+            if !state.unicode
+            {
+                match b
+                {
+                    0x80..=0xff => push_scalar(&mut units,
+                        state.windows[state.active] + b as u32 - 0x80),
+                    _ if is_literal(b as u32) => units.push(Some(b as u16)),
+                    0x01..=0x08 => match argument(1)
+                    {
+                        Some(&[q]) =>
+                        {
+                            let n = (b - SQ0) as usize;
+                            let scalar = if q < 0x80
+                            {
+                                STATIC_WINDOWS[n] + q as u32
+                            }
+                            else
+                            {
+                                state.windows[n] + q as u32 - 0x80
+                            };
+                            push_scalar(&mut units, scalar);
+                            i += 1;
+                        },
+                        _ => units.push(None)
+                    },
+                    SDX => match argument(2)
+                    {
+                        Some(&[high, low]) =>
+                        {
+                            state.define_extended(high, low);
+                            i += 2;
+                        },
+                        _ =>
+                        {
+                            units.push(None);
+                            i = bytes.len();
+                        }
+                    },
+                    SQU => match argument(2)
+                    {
+                        Some(&[high, low]) =>
+                        {
+                            units.push(Some(u16::from_be_bytes([high, low])));
+                            i += 2;
+                        },
+                        _ =>
+                        {
+                            units.push(None);
+                            i = bytes.len();
+                        }
+                    },
+                    SCU => state.unicode = true,
+                    0x10..=0x17 => state.active = (b - SC0) as usize,
+                    0x18..=0x1f => match offset()
+                    {
+                        Some(Some(offset)) =>
+                        {
+                            let n = (b - SD0) as usize;
+                            state.windows[n] = offset;
+                            state.active = n;
+                            i += 1;
+                        },
+                        Some(None) =>
+                        {
+                            // Reserved offset
+                            units.push(None);
+                            i += 1;
+                        },
+                        None => units.push(None)
+                    },
+                    // Reserved
+                    _ => units.push(None)
+                }
+            }
+            else
+            {
+                match b
+                {
+                    0xe0..=0xe7 =>
+                    {
+                        state.active = (b - UC0) as usize;
+                        state.unicode = false;
+                    },
+                    0xe8..=0xef => match offset()
+                    {
+                        Some(Some(offset)) =>
+                        {
+                            let n = (b - UD0) as usize;
+                            state.windows[n] = offset;
+                            state.active = n;
+                            state.unicode = false;
+                            i += 1;
+                        },
+                        Some(None) =>
+                        {
+                            // Reserved offset
+                            units.push(None);
+                            i += 1;
+                        },
+                        None => units.push(None)
+                    },
+                    UQU => match argument(2)
+                    {
+                        Some(&[high, low]) =>
+                        {
+                            units.push(Some(u16::from_be_bytes([high, low])));
+                            i += 2;
+                        },
+                        _ =>
+                        {
+                            units.push(None);
+                            i = bytes.len();
+                        }
+                    },
+                    UDX => match argument(2)
+                    {
+                        Some(&[high, low]) =>
+                        {
+                            state.define_extended(high, low);
+                            state.unicode = false;
+                            i += 2;
+                        },
+                        _ =>
+                        {
+                            units.push(None);
+                            i = bytes.len();
+                        }
+                    },
+                    // Reserved
+                    0xf2 => units.push(None),
+                    _ => match argument(1)
+                    {
+                        Some(&[low]) =>
+                        {
+                            units.push(Some(u16::from_be_bytes([b, low])));
+                            i += 1;
+                        },
+                        _ => units.push(None)
+                    }
+                }
+            }
+        }
+
+        let mut decoded = String::new();
+        for (k, run) in units.split(Option::is_none).enumerate()
+        {
+            if 0 < k
+            {
+                decoded.push(replacement?);
+            }
+            decoded.push_str(&char::decode_utf16(run.iter().flatten().copied())
+                // Iterator<Result<char, DecodeUtf16Error>>
+                .map(|r| r.ok().or(replacement))
+                .collect::<Option<String>>()?);
+        }
+        Some(decoded)
+    }
+}
+
+impl Default for ScsuEngine
+{
+    fn default() -> Self { ScsuEngine::new() }
+}
+
+impl ScsuEngineBuilder
+{
+    /// Set the character to decode invalid sequences as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> ScsuEngine { self.engine }
+}
+
+impl Engine for ScsuEngine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("SCSU", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+        let mut state = State::new();
+
+        let mut chars = string.chars().peekable();
+        while let Some(c) = chars.next()
+        {
+            let next = chars.peek().copied();
+            if state.unicode
+            {
+                state.encode_unicode(c, next, &mut encoded);
+            }
+            else
+            {
+                state.encode_single_byte(c, next, &mut encoded);
+            }
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        ScsuEngine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        ScsuEngine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = ScsuEngine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        // Examples from UTS #6
+        let encoded = engine.encode("Öl fließt").unwrap();
+        assert_eq!(encoded, &[0xd6, 0x6c, 0x20, 0x66, 0x6c, 0x69, 0x65, 0xdf,
+            0x74]);
+
+        let encoded = engine.encode("Москва").unwrap();
+        assert_eq!(encoded, &[0x12, 0x9c, 0xbe, 0xc1, 0xba, 0xb2, 0xb0]);
+
+        let encoded = engine.encode("東京").unwrap();
+        assert_eq!(encoded, &[0x0f, 0x67, 0x71, 0x4e, 0xac]);
+
+        let encoded = engine.encode("a\u{1}😀").unwrap();
+        assert_eq!(encoded, &[0x61, 0x01, 0x01, 0x0b, 0x01, 0xec, 0x80]);
+
+        // Round trip
+        let string = "Αθήνα, 東京 ミク \u{e000}😀 Clément";
+        assert_eq!(engine.decode(&engine.encode(string).unwrap()), string);
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = ScsuEngine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xd6, 0x6c, 0x20, 0x66, 0x6c, 0x69, 0x65,
+            0xdf, 0x74]);
+        assert_eq!(decoded, "Öl fließt");
+
+        let decoded = engine.decode(&[0x0f, 0x67, 0x71, 0x4e, 0xac, 0xe0,
+            0x21]);
+        assert_eq!(decoded, "東京!");
+
+        let decoded = engine.decode(&[0x0c, 0x41, 0x0e, 0x00]);
+        // Reserved tag, then truncated quote
+        assert_eq!(decoded, "�A�");
+
+        let engine = ScsuEngine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "SCSU (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x0c, 0x0e, 0xd8]);
+        // Reserved tag, and truncated quoted unit
+        assert_eq!(decoded, "A??");
+
+        assert!(engine.try_decode(&[0x41, 0x0c]).is_none());
+
+        let decoded = engine.try_decode(&[0x0f, 0xff, 0xfd]);
+        assert_eq!(decoded.unwrap(), "�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = ScsuEngine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
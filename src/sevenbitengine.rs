@@ -0,0 +1,94 @@
+//! Deencoding engine for 7-bit transports
+//!
+//! Some old transports (mail gateways, serial links, terminals) only carry 7
+//! bits per byte, and silently clear the high bit of every byte that goes
+//! through them. For example, `'é'` encoded as Latin-1 is `0xE9`, which
+//! becomes `0x69`, so `"Clément"` turns into `"Climent"`.
+//!
+//! Encoding is plain ASCII, and fails outside of it. Decoding masks every byte
+//! to 7 bits, so there is nothing to configure a replacement for.
+
+use crate::engine::Engine;
+
+pub struct SevenBitEngine {}
+
+impl SevenBitEngine
+{
+    /// The engine.
+    pub const fn new() -> Self { SevenBitEngine {} }
+}
+
+impl Default for SevenBitEngine
+{
+    fn default() -> Self { SevenBitEngine::new() }
+}
+
+impl Engine for SevenBitEngine
+{
+    fn get_name(&self) -> String { "7-bit ASCII".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        if string.is_ascii()
+        {
+            Some(Vec::from(string.as_bytes()))
+        }
+        else
+        {
+            None
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        bytes.iter().map(|&b| (b & 0x7f) as char).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+
+    #[test]
+    fn encode() {
+        let engine = SevenBitEngine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        assert!(engine.encode("é").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = SevenBitEngine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe9]);
+        // 1110 1001
+        // => 0110 1001
+        assert_eq!(decoded, "i");
+
+        let encoded = Latin1Engine::new().encode("Clément").unwrap();
+        let decoded = engine.decode(&encoded);
+        assert_eq!(decoded, "Climent");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = SevenBitEngine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,163 @@
+//! Deencoding engine keeping the undefined bytes of a single byte engine
+//!
+//! The Windows code pages leave a few bytes undefined, such as `0x81` in
+//! Codepage 1252, which the other engines decode as U+FFFD � REPLACEMENT
+//! CHARACTER. But many decoders, like Python's ftfy with its "sloppy" codecs,
+//! or the WHATWG encoding standard for some of them, map those bytes to the
+//! scalar with the same value instead, which for `0x80` to `0x9F` is a C1
+//! control code. Double encoded UTF-8 can then be recovered: `'Á'` is `C3 81`
+//! in UTF-8, which only survives a round trip through Codepage 1252 that way.
+//!
+//! This engine wraps a single byte engine. Decoding uses the inner engine for
+//! defined bytes, and keeps the others. Encoding uses the inner engine, and
+//! falls back to the undefined byte with the same value as the scalar.
+
+use crate::engine::Engine;
+
+pub struct SloppyEngine<'a, E: Engine + ?Sized>
+{
+    /// The inner single byte engine.
+    pub engine: &'a E
+}
+
+impl<E: Engine + ?Sized> SloppyEngine<'_, E>
+{
+    /// Whether the inner engine leaves `byte` undefined, whatever it decodes
+    /// undefined bytes as.
+    fn is_undefined(&self, byte: u8) -> bool
+    {
+        self.engine.try_decode(&[byte]).is_none()
+    }
+}
+
+impl<E: Engine + ?Sized> Engine for SloppyEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        format!("{} (sloppy)", self.engine.get_name())
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            let mut buf = [0u8; 4];
+            match self.engine.encode(c.encode_utf8(&mut buf))
+            {
+                Some(bytes) => encoded.extend(bytes),
+                None =>
+                {
+                    let byte = u8::try_from(c).ok()
+                        .filter(|&b| self.is_undefined(b))?;
+                    encoded.push(byte);
+                }
+            }
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let mut decoded = String::new();
+
+        // Runs of defined bytes are decoded together, for engines composing
+        // characters across bytes
+        for run in bytes.split_inclusive(|&b| self.is_undefined(b))
+        {
+            match run.split_last()
+            {
+                Some((&last, defined)) if self.is_undefined(last) =>
+                {
+                    decoded.push_str(&self.engine.decode(defined));
+                    decoded.push(last as char);
+                },
+                _ => decoded.push_str(&self.engine.decode(run))
+            }
+        }
+
+        decoded
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let mut decoded = String::new();
+
+        for run in bytes.split_inclusive(|&b| self.is_undefined(b))
+        {
+            match run.split_last()
+            {
+                Some((&last, defined)) if self.is_undefined(last) =>
+                {
+                    decoded.push_str(&self.engine.try_decode(defined)?);
+                    decoded.push(last as char);
+                },
+                _ => decoded.push_str(&self.engine.try_decode(run)?)
+            }
+        }
+
+        Some(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cp1253engine::CP1253Engine;
+    use crate::cp1258engine::CP1258Engine;
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+
+    #[test]
+    fn encode() {
+        let engine = SloppyEngine { engine: &CP1253Engine::new() };
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("α\u{81}\u{aa}").unwrap();
+        assert_eq!(encoded, &[0xe1, 0x81, 0xaa]);
+
+        // Defined as U+0385
+        assert!(engine.encode("\u{a1}").is_none());
+        assert!(engine.encode("é").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = SloppyEngine { engine: &Latin1Engine::new() };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xc3, 0x81, 0x80]);
+        assert_eq!(decoded, "Ã\u{81}€");
+
+        let latin1 = Latin1Engine::builder().replacement('?').build();
+        let engine = SloppyEngine { engine: &latin1 };
+
+        let decoded = engine.decode(&[0xc3, 0x81, 0x80]);
+        assert_eq!(decoded, "Ã\u{81}€");
+
+        let engine = SloppyEngine { engine: &CP1258Engine::new() };
+
+        let decoded = engine.decode(&[0x61, 0xde, 0x81, 0x65, 0xec]);
+        assert_eq!(decoded, "ã\u{81}é");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = SloppyEngine { engine: &CP1253Engine::new() };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
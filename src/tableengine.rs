@@ -0,0 +1,410 @@
+//! Deencoding engine for user-defined single byte charsets
+//!
+//! Obscure vendor code pages are not supported by any crate, but their
+//! mappings are usually available as text files. This engine is built at
+//! runtime from either:
+//! * a Unicode consortium mapping file, as found on
+//!   <https://www.unicode.org/Public/MAPPINGS/>, with one `0xE9\t0x00E9` pair
+//!   of byte and scalar per line, and comments starting with `#`; bytes
+//!   without a scalar are undefined;
+//! * an ICU `.ucm` file, with one `<U00E9> \xE9 |0` mapping per line between
+//!   `CHARMAP` and `END CHARMAP`. The precision indicator `|0` marks a
+//!   round-trip mapping, `|1` a fallback only used for encoding, and `|3` a
+//!   reverse fallback only used for decoding. `|2` (substitution) mappings are
+//!   ignored.
+//!
+//! Only single byte charsets, mapping each byte to a single scalar, are
+//! supported.
+//!
+//! Decoding an undefined byte follows [`TableEngine::undefined`]. Encoding
+//! fails on scalars without a mapping; when several bytes map to the same
+//! scalar, [`TableEngine::reverse`] chooses between them.
+
+use crate::engine::Engine;
+
+use std::fmt;
+use std::path::Path;
+
+/// How to decode bytes without a mapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Undefined
+{
+    /// Decode as U+FFFD � REPLACEMENT CHARACTER.
+    Replacement,
+    /// Decode as the scalar with the same value as the byte, like Latin-1
+    /// would.
+    Passthrough,
+    /// Drop the byte.
+    Skip
+}
+
+/// Which byte to encode a scalar to, when several map to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reverse
+{
+    /// The first byte in the order of the file.
+    First,
+    /// The last byte in the order of the file.
+    Last,
+    /// The lowest byte.
+    Lowest,
+    /// The highest byte.
+    Highest
+}
+
+/// The direction in which a mapping is used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction
+{
+    /// Used for encoding and decoding.
+    RoundTrip,
+    /// Only used for encoding.
+    Fallback,
+    /// Only used for decoding.
+    ReverseFallback
+}
+
+/// An error while loading a mapping table.
+#[derive(Debug)]
+pub enum TableError
+{
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// A line of the file could not be parsed.
+    Syntax
+    {
+        /// The line number, starting at 1.
+        line: usize,
+        /// The reason the line could not be parsed.
+        message: String
+    }
+}
+
+impl fmt::Display for TableError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            TableError::Io(error) => write!(f, "{}", error),
+            TableError::Syntax { line, message } =>
+                write!(f, "line {}: {}", line, message)
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<std::io::Error> for TableError
+{
+    fn from(error: std::io::Error) -> Self
+    {
+        TableError::Io(error)
+    }
+}
+
+pub struct TableEngine
+{
+    /// The name of the engine.
+    pub name: String,
+    /// How to decode bytes without a mapping.
+    pub undefined: Undefined,
+    /// Which byte to encode a scalar to, when several map to it.
+    pub reverse: Reverse,
+    /// The mappings, in the order of the file.
+    mappings: Vec<(u8, char, Direction)>
+}
+
+/// Parse a hexadecimal number, with an optional `0x` prefix.
+fn parse_hex(text: &str, line: usize) -> Result<u32, TableError>
+{
+    let digits = text.strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u32::from_str_radix(digits, 16).map_err(|_| TableError::Syntax {
+        line, message: format!("invalid hexadecimal number \"{}\"", text)
+    })
+}
+
+/// Parse a single byte.
+fn parse_byte(text: &str, line: usize) -> Result<u8, TableError>
+{
+    u8::try_from(parse_hex(text, line)?).map_err(|_| TableError::Syntax {
+        line, message: format!("\"{}\" is not a single byte", text)
+    })
+}
+
+/// Parse a single scalar.
+fn parse_scalar(text: &str, line: usize) -> Result<char, TableError>
+{
+    char::from_u32(parse_hex(text, line)?).ok_or(TableError::Syntax {
+        line, message: format!("\"{}\" is not a Unicode scalar", text)
+    })
+}
+
+impl TableEngine
+{
+    /// Build an engine from the content of a Unicode consortium mapping file.
+    pub fn from_mapping(name: &str, source: &str)
+        -> Result<TableEngine, TableError>
+    {
+        let mut mappings = Vec::new();
+
+        for (i, line) in source.lines().enumerate()
+        {
+            let line_number = i + 1;
+            let content = line.split('#').next().unwrap_or("");
+            let mut fields = content.split_whitespace();
+            let Some(byte) = fields.next()
+            else
+            {
+                continue;
+            };
+            let byte = parse_byte(byte, line_number)?;
+            if let Some(scalar) = fields.next()
+            {
+                let scalar = parse_scalar(scalar, line_number)?;
+                mappings.push((byte, scalar, Direction::RoundTrip));
+            }
+        }
+
+        Ok(TableEngine {
+            name: name.to_string(),
+            undefined: Undefined::Replacement,
+            reverse: Reverse::First,
+            mappings
+        })
+    }
+
+    /// Build an engine from the content of an ICU `.ucm` file.
+    pub fn from_ucm(name: &str, source: &str)
+        -> Result<TableEngine, TableError>
+    {
+        let mut mappings = Vec::new();
+        let mut in_charmap = false;
+
+        for (i, line) in source.lines().enumerate()
+        {
+            let line_number = i + 1;
+            let content = line.split('#').next().unwrap_or("").trim();
+            if content == "CHARMAP"
+            {
+                in_charmap = true;
+                continue;
+            }
+            if content == "END CHARMAP"
+            {
+                in_charmap = false;
+                continue;
+            }
+            if !in_charmap || content.is_empty()
+            {
+                continue;
+            }
+
+            let syntax = |message: &str| TableError::Syntax {
+                line: line_number, message: message.to_string()
+            };
+            let mut fields = content.split_whitespace();
+            let (Some(scalar), Some(bytes)) = (fields.next(), fields.next())
+            else
+            {
+                return Err(syntax("expected a scalar and bytes"));
+            };
+            let scalar = scalar.strip_prefix("<U")
+                .and_then(|s| s.strip_suffix('>'))
+                .ok_or(syntax("expected a single <UXXXX> scalar"))?;
+            let scalar = parse_scalar(scalar, line_number)?;
+            let byte = bytes.strip_prefix("\\x")
+                .filter(|b| b.len() == 2)
+                .ok_or(syntax("expected a single \\xNN byte"))?;
+            let byte = parse_byte(byte, line_number)?;
+            let direction = match fields.next()
+            {
+                None | Some("|0") => Direction::RoundTrip,
+                Some("|1") => Direction::Fallback,
+                Some("|3") => Direction::ReverseFallback,
+                Some("|2") => continue,
+                Some(_) => return Err(syntax("invalid precision indicator"))
+            };
+            mappings.push((byte, scalar, direction));
+        }
+
+        Ok(TableEngine {
+            name: name.to_string(),
+            undefined: Undefined::Replacement,
+            reverse: Reverse::First,
+            mappings
+        })
+    }
+
+    /// Build an engine from a file, named after the file. Files with the
+    /// `.ucm` extension are read as ICU files, others as Unicode consortium
+    /// mapping files.
+    pub fn from_file(path: &Path) -> Result<TableEngine, TableError>
+    {
+        let source = std::fs::read_to_string(path)?;
+        let name = path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if path.extension().is_some_and(|extension| extension == "ucm")
+        {
+            TableEngine::from_ucm(&name, &source)
+        }
+        else
+        {
+            TableEngine::from_mapping(&name, &source)
+        }
+    }
+}
+
+impl Engine for TableEngine
+{
+    fn get_name(&self) -> String { self.name.clone() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            let candidates = |direction: Direction|
+                self.mappings.iter()
+                    .filter(move |(_, s, d)| *s == c && *d == direction)
+                    .map(|(b, _, _)| *b);
+            let mut bytes: Vec<u8> = candidates(Direction::RoundTrip).collect();
+            if bytes.is_empty()
+            {
+                bytes = candidates(Direction::Fallback).collect();
+            }
+            let byte = match self.reverse
+            {
+                Reverse::First => bytes.first().copied(),
+                Reverse::Last => bytes.last().copied(),
+                Reverse::Lowest => bytes.iter().min().copied(),
+                Reverse::Highest => bytes.iter().max().copied()
+            }?;
+            encoded.push(byte);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let mut decoded = String::new();
+
+        for &b in bytes
+        {
+            let mapping = self.mappings.iter()
+                .find(|(m, _, d)| *m == b && *d != Direction::Fallback);
+            match (mapping, self.undefined)
+            {
+                (Some((_, c, _)), _) => decoded.push(*c),
+                (None, Undefined::Replacement) =>
+                    decoded.push(char::REPLACEMENT_CHARACTER),
+                (None, Undefined::Passthrough) => decoded.push(b as char),
+                (None, Undefined::Skip) => ()
+            }
+        }
+
+        decoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    const MAPPING: &str = "\
+# A made-up code page
+0x41\t0x0041\t# LATIN CAPITAL LETTER A
+0x80\t0x20AC\t# EURO SIGN
+0x81\t      \t# UNDEFINED
+0xE9\t0x00E9\t# LATIN SMALL LETTER E WITH ACUTE
+0xEA\t0x00E9\t# LATIN SMALL LETTER E WITH ACUTE, again
+";
+
+    const UCM: &str = "\
+<code_set_name> \"made-up\"
+<uconv_class> \"SBCS\"
+CHARMAP
+<U0041> \\x41 |0
+<U00E9> \\xE9 |0
+<U00C9> \\x45 |1
+<U0152> \\x8C |3
+END CHARMAP
+";
+
+    #[test]
+    fn encode() {
+        let mut engine = TableEngine::from_mapping("made-up", MAPPING).unwrap();
+
+        let encoded = engine.encode("Aé€").unwrap();
+        assert_eq!(encoded, &[0x41, 0xe9, 0x80]);
+
+        assert!(engine.encode("B").is_none());
+
+        engine.reverse = Reverse::Last;
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xea]);
+
+        let engine = TableEngine::from_ucm("made-up", UCM).unwrap();
+
+        let encoded = engine.encode("AÉé").unwrap();
+        // Fallback
+        assert_eq!(encoded, &[0x41, 0x45, 0xe9]);
+
+        assert!(engine.encode("Œ").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let mut engine = TableEngine::from_mapping("made-up", MAPPING).unwrap();
+
+        let decoded = engine.decode(&[0x41, 0xea, 0x80]);
+        assert_eq!(decoded, "Aé€");
+
+        let decoded = engine.decode(&[0x41, 0x81]);
+        assert_eq!(decoded, "A�");
+
+        engine.undefined = Undefined::Passthrough;
+        let decoded = engine.decode(&[0x41, 0x81]);
+        assert_eq!(decoded, "A\u{81}");
+
+        engine.undefined = Undefined::Skip;
+        let decoded = engine.decode(&[0x41, 0x81]);
+        assert_eq!(decoded, "A");
+
+        let engine = TableEngine::from_ucm("made-up", UCM).unwrap();
+
+        let decoded = engine.decode(&[0x41, 0x45, 0x8c]);
+        // Reverse fallback
+        assert_eq!(decoded, "A�Œ");
+    }
+
+    #[test]
+    fn errors()
+    {
+        let error = TableEngine::from_mapping("bad", "0x41 0x41\n0x8140 0x3000")
+            .err().unwrap();
+        assert_eq!(error.to_string(), "line 2: \"0x8140\" is not a single byte");
+
+        let error = TableEngine::from_ucm("bad", "CHARMAP\n<U0041> \\x41\\x42")
+            .err().unwrap();
+        assert_eq!(error.to_string(), "line 2: expected a single \\xNN byte");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = TableEngine::from_ucm("made-up", UCM).unwrap();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+    }
+}
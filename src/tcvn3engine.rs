@@ -0,0 +1,200 @@
+//! Deencoding engine for TCVN3 (ABC)
+//!
+//! TCVN3 is the VN3 subset of TCVN 5712:1993, used by the `.Vn` "ABC" fonts
+//! that were the de facto standard in northern Vietnam before Unicode. It only
+//! has precomposed lowercase letters, and capital letters without a tone mark:
+//! capital letters with a tone mark were written with a separate font, using
+//! the lowercase positions. Text typed that way decodes as lowercase.
+//!
+//! Encoding and decoding are performed with a table, since no crate supports
+//! TCVN3. Encoding fails on characters outside of the table, and unassigned
+//! bytes are decoded as U+FFFD � REPLACEMENT CHARACTER, or as another
+//! character configured with [`Tcvn3Engine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+pub struct Tcvn3Engine
+{
+    /// The character to decode unassigned bytes as.
+    pub replacement: char
+}
+
+/// Builder for a [`Tcvn3Engine`].
+pub struct Tcvn3EngineBuilder
+{
+    engine: Tcvn3Engine
+}
+
+impl Tcvn3Engine
+{
+    /// The default engine, decoding unassigned bytes as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Tcvn3Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Tcvn3EngineBuilder
+    {
+        Tcvn3EngineBuilder { engine: Tcvn3Engine::new() }
+    }
+
+    /// Decode, with unassigned bytes as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        bytes.iter()
+            .map(|&b| match b
+            {
+                0x00..=0x7f => Some(b as char),
+                0x80..=0x9f => replacement,
+                _ => match UPPER[(b - 0xa0) as usize]
+                {
+                    '\0' => replacement,
+                    c => Some(c)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Tcvn3Engine
+{
+    fn default() -> Self { Tcvn3Engine::new() }
+}
+
+impl Tcvn3EngineBuilder
+{
+    /// Set the character to decode unassigned bytes as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Tcvn3Engine { self.engine }
+}
+
+/// The upper half of TCVN3, from `0xA0` to `0xFF`. Unassigned bytes are mapped
+/// to U+0000.
+const UPPER: [char; 96] = [
+    // A0
+    '\u{a0}', 'Ă', 'Â', 'Ê', 'Ô', 'Ơ', 'Ư', 'Đ',
+    'ă', 'â', 'ê', 'ô', 'ơ', 'ư', 'đ', '\0',
+    // B0
+    '\0', '\0', '\0', '\0', '\0', 'à', 'ả', 'ã',
+    'á', 'ạ', '\0', 'ằ', 'ẳ', 'ẵ', 'ắ', '\0',
+    // C0
+    '\0', '\0', '\0', '\0', '\0', '\0', 'ặ', 'ầ',
+    'ẩ', 'ẫ', 'ấ', 'ậ', 'è', '\0', 'ẻ', 'ẽ',
+    // D0
+    'é', 'ẹ', 'ề', 'ể', 'ễ', 'ế', 'ệ', 'ì',
+    'ỉ', '\0', '\0', '\0', 'ĩ', 'í', 'ị', 'ò',
+    // E0
+    '\0', 'ỏ', 'õ', 'ó', 'ọ', 'ồ', 'ổ', 'ỗ',
+    'ố', 'ộ', 'ờ', 'ở', 'ỡ', 'ớ', 'ợ', 'ù',
+    // F0
+    '\0', 'ủ', 'ũ', 'ú', 'ụ', 'ừ', 'ử', 'ữ',
+    'ứ', 'ự', 'ỳ', 'ỷ', 'ỹ', 'ý', 'ỵ', '\0',
+];
+
+impl Engine for Tcvn3Engine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("TCVN3 (ABC)", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            if (c as u32) < 0x80
+            {
+                encoded.push(c as u8);
+                continue;
+            }
+            let i = UPPER.iter().position(|&u| u == c && u != '\0')?;
+            encoded.push(0xa0 + i as u8);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        Tcvn3Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Tcvn3Engine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = Tcvn3Engine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("Nguyễn").unwrap();
+        assert_eq!(encoded, &[0x4e, 0x67, 0x75, 0x79, 0xd4, 0x6e]);
+
+        let encoded = engine.encode("Đà").unwrap();
+        assert_eq!(encoded, &[0xa7, 0xb5]);
+
+        assert!(engine.encode("À").is_none());
+        assert!(engine.encode("€").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = Tcvn3Engine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0x56, 0x69, 0xd6, 0x74]);
+        assert_eq!(decoded, "Việt");
+
+        let decoded = engine.decode(&[0x80, 0xb0]);
+        assert_eq!(decoded, "��");
+
+        let engine = Tcvn3Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "TCVN3 (ABC) (errors as '?')");
+
+        let decoded = engine.decode(&[0x41, 0x80, 0xb0]);
+        assert_eq!(decoded, "A??");
+
+        assert!(engine.try_decode(&[0x41, 0x80]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Tcvn3Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,189 @@
+//! Deencoding engine for ASCII transliteration
+//!
+//! Many legacy systems cannot store anything outside of ASCII, and replace
+//! accented letters with their base letter: `"Clément"` becomes `"Clement"`.
+//!
+//! Encoding applies the compatibility decomposition (NFKD) of
+//! [`unicode-normalization`](https://crates.io/crates/unicode-normalization)
+//! and drops the combining marks, then looks the remaining scalars up in a
+//! table for letters that do not decompose, such as `'ß'` or `'Ł'`. Encoding
+//! fails if a scalar is still not ASCII after that.
+//!
+//! Decoding is plain ASCII, with U+FFFD � REPLACEMENT CHARACTER for bytes
+//! outside of it, or another character configured with
+//! [`TranslitEngine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+pub struct TranslitEngine
+{
+    /// The character to decode bytes outside of ASCII as.
+    pub replacement: char
+}
+
+/// Builder for a [`TranslitEngine`].
+pub struct TranslitEngineBuilder
+{
+    engine: TranslitEngine
+}
+
+impl TranslitEngine
+{
+    /// The default engine, decoding bytes outside of ASCII as U+FFFD.
+    pub const fn new() -> Self
+    {
+        TranslitEngine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> TranslitEngineBuilder
+    {
+        TranslitEngineBuilder { engine: TranslitEngine::new() }
+    }
+
+    /// Decode, with bytes outside of ASCII as `replacement`, or failing
+    /// without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        bytes.iter()
+            .map(|&b| if b.is_ascii() { Some(b as char) } else { replacement })
+            .collect()
+    }
+}
+
+impl Default for TranslitEngine
+{
+    fn default() -> Self { TranslitEngine::new() }
+}
+
+impl TranslitEngineBuilder
+{
+    /// Set the character to decode bytes outside of ASCII as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> TranslitEngine { self.engine }
+}
+
+/// Transliterations for scalars that have no ASCII decomposition.
+const TABLE: &[(char, &str)] = &[
+    ('Æ', "AE"), ('æ', "ae"), ('Ð', "D"), ('ð', "d"), ('Ø', "O"), ('ø', "o"),
+    ('Þ', "Th"), ('þ', "th"), ('ß', "ss"), ('Đ', "D"), ('đ', "d"), ('Ħ', "H"),
+    ('ħ', "h"), ('ı', "i"), ('ĸ', "k"), ('Ł', "L"), ('ł', "l"), ('Ŋ', "NG"),
+    ('ŋ', "ng"), ('Œ', "OE"), ('œ', "oe"), ('Ŧ', "T"), ('ŧ', "t"), ('ƒ', "f"),
+    ('ẞ', "SS"), ('‐', "-"), ('‑', "-"), ('‒', "-"), ('–', "-"), ('—', "-"),
+    ('‘', "'"), ('’', "'"), ('‚', ","), ('“', "\""), ('”', "\""), ('„', "\""),
+    ('«', "<<"), ('»', ">>"), ('‹', "<"), ('›', ">"), ('·', "."), ('•', "*"),
+    ('×', "x"), ('÷', "/"), ('⁄', "/"), ('€', "EUR"), ('£', "GBP"),
+    ('¥', "JPY"), ('©', "(C)"), ('®', "(R)"), ('°', "deg"),
+];
+
+impl Engine for TranslitEngine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("ASCII transliteration", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = String::new();
+
+        for c in string.nfkd().filter(|&c| !is_combining_mark(c))
+        {
+            if c.is_ascii()
+            {
+                encoded.push(c);
+            }
+            else
+            {
+                let (_, transliteration) =
+                    TABLE.iter().find(|(from, _)| *from == c)?;
+                encoded.push_str(transliteration);
+            }
+        }
+
+        Some(encoded.into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        TranslitEngine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        TranslitEngine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = TranslitEngine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, b"Hello");
+
+        let encoded = engine.encode("Clément").unwrap();
+        assert_eq!(encoded, b"Clement");
+
+        let encoded = engine.encode("Łódź Straße").unwrap();
+        assert_eq!(encoded, b"Lodz Strasse");
+
+        let encoded = engine.encode("ﬁ½").unwrap();
+        // Compatibility decompositions
+        assert_eq!(encoded, b"fi1/2");
+
+        assert!(engine.encode("ミク").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = TranslitEngine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xe8]);
+        assert_eq!(decoded, "�");
+
+        let engine = TranslitEngine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "ASCII transliteration (errors as '?')");
+
+        let decoded = engine.decode(&[0x43, 0x6c, 0xe9]);
+        assert_eq!(decoded, "Cl?");
+
+        assert!(engine.try_decode(&[0x43, 0x6c, 0xe9]).is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = TranslitEngine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}
@@ -0,0 +1,214 @@
+//! Deencoding engine cutting the output of another engine to a fixed length
+//!
+//! Storage with a length limit in bytes, like a `VARCHAR(10)` column in byte
+//! semantics, cuts multi-byte characters in half, which leaves a trailing
+//! U+FFFD � REPLACEMENT CHARACTER when read back. Fixed-width records, as
+//! found on mainframes, additionally pad shorter fields with NULs or spaces.
+//!
+//! This engine wraps another engine, and cuts (and optionally pads) the bytes
+//! to [`TruncatedEngine::length`] after encoding, and before decoding.
+//!
+//! When counting in [`Unit::Utf16`] code units instead, the way JavaScript's
+//! `substring()` and `maxlength` do, the string is cut before encoding and
+//! after decoding. A surrogate pair cut in half leaves a lone surrogate, which
+//! becomes U+FFFD, as it would when later converted to UTF-8.
+
+use crate::engine::Engine;
+
+/// The unit of the length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit
+{
+    /// Bytes of the encoding, after encoding and before decoding.
+    Bytes,
+    /// UTF-16 code units of the string, before encoding and after decoding.
+    Utf16
+}
+
+pub struct TruncatedEngine<'a, E: Engine + ?Sized>
+{
+    /// The inner engine.
+    pub engine: &'a E,
+    /// The maximum length.
+    pub length: usize,
+    /// The unit of the length.
+    pub unit: Unit,
+    /// The padding up to `length`, if any. In [`Unit::Utf16`], this is the
+    /// scalar with the same value.
+    pub padding: Option<u8>
+}
+
+impl<E: Engine + ?Sized> TruncatedEngine<'_, E>
+{
+    /// Cut and pad `bytes`, in [`Unit::Bytes`].
+    fn cut_bytes(&self, bytes: &[u8]) -> Vec<u8>
+    {
+        let mut bytes = bytes[..bytes.len().min(self.length)].to_vec();
+        if let Some(padding) = self.padding
+        {
+            bytes.resize(self.length, padding);
+        }
+        bytes
+    }
+
+    /// Cut and pad the UTF-16 units of `string`, in [`Unit::Utf16`].
+    fn cut_units(&self, string: &str) -> Vec<u16>
+    {
+        let mut units: Vec<u16> = string.encode_utf16()
+            .take(self.length)
+            .collect();
+        if let Some(padding) = self.padding
+        {
+            units.resize(self.length, padding as u16);
+        }
+        units
+    }
+
+    /// Cut and pad `string`, in [`Unit::Utf16`].
+    fn cut_string(&self, string: &str) -> String
+    {
+        String::from_utf16_lossy(&self.cut_units(string))
+    }
+}
+
+impl<E: Engine + ?Sized> Engine for TruncatedEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        let unit = match self.unit
+        {
+            Unit::Bytes => "bytes",
+            Unit::Utf16 => "UTF-16 code units"
+        };
+        match self.padding
+        {
+            None => format!("{} (truncated to {} {})", self.engine.get_name(),
+                self.length, unit),
+            Some(padding) => format!("{} (fixed width of {} {}, padded with \
+                {:02X})", self.engine.get_name(), self.length, unit, padding)
+        }
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        match self.unit
+        {
+            Unit::Bytes => Some(self.cut_bytes(&self.engine.encode(string)?)),
+            Unit::Utf16 => self.engine.encode(&self.cut_string(string))
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        match self.unit
+        {
+            Unit::Bytes => self.engine.decode(&self.cut_bytes(bytes)),
+            Unit::Utf16 => self.cut_string(&self.engine.decode(bytes))
+        }
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        match self.unit
+        {
+            Unit::Bytes => self.engine.try_decode(&self.cut_bytes(bytes)),
+            Unit::Utf16 => String::from_utf16(
+                &self.cut_units(&self.engine.try_decode(bytes)?)).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let engine = TruncatedEngine {
+            engine: &Utf8Engine::new(), length: 4, unit: Unit::Bytes,
+            padding: None
+        };
+
+        let encoded = engine.encode("Hi").unwrap();
+        assert_eq!(encoded, &[0x48, 0x69]);
+
+        let encoded = engine.encode("Clément").unwrap();
+        assert_eq!(encoded, &[0x43, 0x6c, 0xc3, 0xa9]);
+
+        let encoded = engine.encode("Dvořák").unwrap();
+        assert_eq!(encoded, &[0x44, 0x76, 0x6f, 0xc5]);
+
+        let engine = TruncatedEngine {
+            engine: &Latin1Engine::new(), length: 4, unit: Unit::Bytes,
+            padding: Some(0x20)
+        };
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xe9, 0x20, 0x20, 0x20]);
+
+        assert!(engine.encode("łódź").is_none());
+
+        let engine = TruncatedEngine {
+            engine: &Utf8Engine::new(), length: 2, unit: Unit::Utf16,
+            padding: None
+        };
+
+        let encoded = engine.encode("a😀").unwrap();
+        assert_eq!(encoded, &[0x61, 0xef, 0xbf, 0xbd]);
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = TruncatedEngine {
+            engine: &Utf8Engine::new(), length: 4, unit: Unit::Bytes,
+            padding: None
+        };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "worl");
+
+        let decoded = engine.decode(&[0x44, 0x76, 0x6f, 0xc5, 0x99]);
+        assert_eq!(decoded, "Dvo�");
+
+        let engine = TruncatedEngine {
+            engine: &Latin1Engine::new(), length: 4, unit: Unit::Bytes,
+            padding: Some(0x00)
+        };
+
+        let decoded = engine.decode(&[0xe9]);
+        assert_eq!(decoded, "é\0\0\0");
+
+        let engine = TruncatedEngine {
+            engine: &Utf8Engine::new(), length: 3, unit: Unit::Utf16,
+            padding: None
+        };
+
+        let decoded = engine.decode(&[0xf0, 0x9f, 0x98, 0x80, 0xf0, 0x9f,
+            0x98, 0x80]);
+        assert_eq!(decoded, "😀�");
+
+        assert!(engine.try_decode(&[0xf0, 0x9f, 0x98, 0x80, 0xf0, 0x9f, 0x98,
+            0x80]).is_none());
+
+        let decoded = engine.try_decode(&[0x61, 0x62, 0x63, 0x64]);
+        assert_eq!(decoded.unwrap(), "abc");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = TruncatedEngine {
+            engine: &Utf8Engine::new(), length: 4, unit: Unit::Bytes,
+            padding: None
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+    }
+}
@@ -0,0 +1,179 @@
+//! Deencoding engine adding upper-casing to another engine
+//!
+//! Mainframes, airline systems and many forms only store names in upper case.
+//! Upper-casing at the wrong level mangles the non-ASCII characters further:
+//! * full Unicode upper-casing is the correct one, but is not one-to-one:
+//!   `'ß'` becomes `"SS"`;
+//! * ASCII-only upper-casing of the encoded bytes, as a byte-oriented system
+//!   does, only changes ASCII letters, and leaves the others alone: `"Clément"`
+//!   becomes `"CLéMENT"`, or worse for encodings whose bytes are not ASCII;
+//! * Turkish and Azeri have a dotted `'i'` and a dotless `'ı'`, so that `'i'`
+//!   becomes `'İ'`, which Codepage 1254 has, but Codepage 1252 does not.
+//!
+//! This engine wraps another engine: encoding upper-cases the string before
+//! passing it to the inner engine, or the bytes it gives, and decoding does
+//! the same with the output of the inner engine, or with the bytes it gets.
+
+use crate::engine::Engine;
+
+/// How to upper-case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Uppercase
+{
+    /// Full Unicode upper-casing of the string.
+    Unicode,
+    /// ASCII-only upper-casing of the bytes.
+    AsciiBytes,
+    /// Full Unicode upper-casing of the string, with the Turkish and Azeri
+    /// rules for `'i'`.
+    Turkish
+}
+
+impl Uppercase
+{
+    /// Upper-case `string`.
+    fn uppercase(&self, string: &str) -> String
+    {
+        match self
+        {
+            Uppercase::Unicode => string.to_uppercase(),
+            Uppercase::AsciiBytes => string.to_string(),
+            Uppercase::Turkish => string.split('i')
+                .map(str::to_uppercase)
+                .collect::<Vec<_>>()
+                .join("İ")
+        }
+    }
+
+    /// Upper-case `bytes`.
+    fn uppercase_bytes(&self, bytes: &[u8]) -> Vec<u8>
+    {
+        match self
+        {
+            Uppercase::AsciiBytes => bytes.to_ascii_uppercase(),
+            _ => bytes.to_vec()
+        }
+    }
+}
+
+pub struct UppercaseEngine<'a, E: Engine + ?Sized>
+{
+    /// How to upper-case.
+    pub uppercase: Uppercase,
+    /// The inner engine.
+    pub engine: &'a E
+}
+
+impl<E: Engine + ?Sized> Engine for UppercaseEngine<'_, E>
+{
+    fn get_name(&self) -> String
+    {
+        let uppercase = match self.uppercase
+        {
+            Uppercase::Unicode => "upper case",
+            Uppercase::AsciiBytes => "ASCII upper case bytes",
+            Uppercase::Turkish => "Turkish upper case"
+        };
+        format!("{} ({})", self.engine.get_name(), uppercase)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let encoded = self.engine.encode(&self.uppercase.uppercase(string))?;
+        Some(self.uppercase.uppercase_bytes(&encoded))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        let bytes = self.uppercase.uppercase_bytes(bytes);
+        let decoded = self.engine.decode(&bytes);
+        self.uppercase.uppercase(&decoded)
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        let bytes = self.uppercase.uppercase_bytes(bytes);
+        let decoded = self.engine.try_decode(&bytes)?;
+        Some(self.uppercase.uppercase(&decoded))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cp1254engine::CP1254Engine;
+    use crate::engine::conformance;
+    use crate::latin1engine::Latin1Engine;
+    use crate::utf8engine::Utf8Engine;
+
+    #[test]
+    fn encode() {
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::Unicode, engine: &Utf8Engine::new()
+        };
+
+        let encoded = engine.encode("Straße").unwrap();
+        assert_eq!(encoded, b"STRASSE");
+
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::AsciiBytes, engine: &Utf8Engine::new()
+        };
+
+        let encoded = engine.encode("Clément").unwrap();
+        assert_eq!(encoded, b"CL\xc3\xa9MENT");
+
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::Turkish, engine: &CP1254Engine::new()
+        };
+
+        let encoded = engine.encode("Yiğit").unwrap();
+        assert_eq!(encoded, &[0x59, 0xdd, 0xd0, 0xdd, 0x54]);
+
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::Turkish, engine: &Latin1Engine::new()
+        };
+
+        assert!(engine.encode("Yiğit").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::AsciiBytes, engine: &Utf8Engine::new()
+        };
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "WORLD!");
+
+        let decoded = engine.decode(b"Cl\xc3\xa9ment");
+        assert_eq!(decoded, "CLéMENT");
+
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::AsciiBytes, engine: &Latin1Engine::new()
+        };
+
+        let decoded = engine.decode(b"Cl\xc3\xa9ment");
+        assert_eq!(decoded, "CLÃ©MENT");
+
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::Turkish, engine: &Latin1Engine::new()
+        };
+
+        let decoded = engine.decode(b"Istanbul, \xfdzmir");
+        assert_eq!(decoded, "ISTANBUL, ÝZMİR");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = UppercaseEngine {
+            uppercase: Uppercase::Turkish, engine: &CP1254Engine::new()
+        };
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+    }
+}
@@ -0,0 +1,267 @@
+//! Deencoding engine for UTF-16
+//!
+//! Both byte orders are supported, without a byte order mark by default.
+//! Reading UTF-16 written in the other byte order, which swaps the bytes of
+//! every unit, is therefore a matter of encoding with one and decoding with the
+//! other.
+//!
+//! Decoding replaces lone surrogates, and a trailing byte that does not make a
+//! full unit, with U+FFFD � REPLACEMENT CHARACTER by default.
+//!
+//! The engine can be configured with [`Utf16Engine::builder()`], to write a
+//! byte order mark and sniff one when reading, or to decode invalid units as
+//! another character.
+//!
+//! With the `bom` option, decoding sniffs any byte order mark and switches
+//! encodings accordingly, like a [`BomEngine`](crate::bomengine::BomEngine)
+//! wrapping the engine; the rest is decoded with the configuration of the
+//! engine when the mark is of its byte order.
+
+use crate::bomengine::{decode_after, sniff, Bom};
+use crate::engine::{name_with_options, Engine};
+
+/// The order of the bytes in a unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness
+{
+    /// Most significant byte first.
+    Big,
+    /// Least significant byte first.
+    Little
+}
+
+pub struct Utf16Engine
+{
+    /// The order of the bytes in a unit.
+    pub endianness: Endianness,
+    /// Whether to write a byte order mark, and sniff one when reading.
+    pub bom: bool,
+    /// The character to decode invalid units as.
+    pub replacement: char
+}
+
+/// Builder for a [`Utf16Engine`].
+pub struct Utf16EngineBuilder
+{
+    engine: Utf16Engine
+}
+
+impl Utf16Engine
+{
+    /// The default engine for a byte order: no byte order mark, and U+FFFD.
+    pub const fn new(endianness: Endianness) -> Self
+    {
+        Utf16Engine {
+            endianness,
+            bom: false,
+            replacement: char::REPLACEMENT_CHARACTER
+        }
+    }
+
+    /// Configure an engine, starting from the default big endian one.
+    pub const fn builder() -> Utf16EngineBuilder
+    {
+        Utf16EngineBuilder { engine: Utf16Engine::new(Endianness::Big) }
+    }
+
+    /// Decode, decoding invalid units as `replacement`, or failing without
+    /// one.
+    fn decode_with(&self, bytes: &[u8], replacement: Option<char>)
+        -> Option<String>
+    {
+        let own = match self.endianness
+        {
+            Endianness::Big => Bom::Utf16BE,
+            Endianness::Little => Bom::Utf16LE
+        };
+        let sniffed = if self.bom { sniff(bytes) } else { None };
+        let bytes = match sniffed
+        {
+            Some((bom, rest)) if bom == own => rest,
+            Some((bom, rest)) => return decode_after(bom, rest, replacement),
+            None => bytes
+        };
+        let chunks = bytes.chunks_exact(2);
+        let orphan = !chunks.remainder().is_empty();
+        let units = chunks.map(|pair| match self.endianness
+        {
+            Endianness::Big => u16::from_be_bytes([pair[0], pair[1]]),
+            Endianness::Little => u16::from_le_bytes([pair[0], pair[1]])
+        });
+
+        let mut decoded = char::decode_utf16(units)
+            // Iterator<Result<char, DecodeUtf16Error>>
+            .map(|r| r.ok().or(replacement))
+            .collect::<Option<String>>()?;
+        if orphan
+        {
+            // Cannot read a second byte
+            decoded.push(replacement?);
+        }
+        Some(decoded)
+    }
+
+    /// The byte order mark, U+FEFF in the byte order of the engine.
+    fn byte_order_mark(&self) -> [u8; 2]
+    {
+        match self.endianness
+        {
+            Endianness::Big => [0xfe, 0xff],
+            Endianness::Little => [0xff, 0xfe]
+        }
+    }
+}
+
+impl Utf16EngineBuilder
+{
+    /// Set the order of the bytes in a unit.
+    pub const fn endianness(mut self, endianness: Endianness) -> Self
+    {
+        self.engine.endianness = endianness;
+        self
+    }
+
+    /// Set whether to write a byte order mark, and sniff one when reading.
+    pub const fn bom(mut self, bom: bool) -> Self
+    {
+        self.engine.bom = bom;
+        self
+    }
+
+    /// Set the character to decode invalid units as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Utf16Engine { self.engine }
+}
+
+impl Engine for Utf16Engine
+{
+    fn get_name(&self) -> String
+    {
+        let name = match self.endianness
+        {
+            Endianness::Big => "UTF-16BE",
+            Endianness::Little => "UTF-16LE"
+        };
+        let mut options = Vec::new();
+        if self.bom
+        {
+            options.push("with BOM".to_string());
+        }
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options(name, &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+        if self.bom
+        {
+            encoded.extend(self.byte_order_mark());
+        }
+        encoded.extend(string.encode_utf16()
+            .flat_map(|unit| match self.endianness
+            {
+                Endianness::Big => unit.to_be_bytes(),
+                Endianness::Little => unit.to_le_bytes()
+            }));
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        self.decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        self.decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = Utf16Engine::new(Endianness::Big);
+
+        let encoded = engine.encode("Hi").unwrap();
+        assert_eq!(encoded, &[0x00, 0x48, 0x00, 0x69]);
+
+        let encoded = engine.encode("😀").unwrap();
+        assert_eq!(encoded, &[0xd8, 0x3d, 0xde, 0x00]);
+
+        let engine = Utf16Engine::new(Endianness::Little);
+
+        let encoded = engine.encode("é😀").unwrap();
+        assert_eq!(encoded, &[0xe9, 0x00, 0x3d, 0xd8, 0x00, 0xde]);
+
+        let engine = Utf16Engine::builder()
+            .endianness(Endianness::Little)
+            .bom(true)
+            .build();
+        assert_eq!(engine.get_name(), "UTF-16LE (with BOM)");
+
+        let encoded = engine.encode("Hi").unwrap();
+        assert_eq!(encoded, &[0xff, 0xfe, 0x48, 0x00, 0x69, 0x00]);
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = Utf16Engine::new(Endianness::Little);
+
+        let decoded = engine.decode(&[0x77, 0x00, 0x6f, 0x00, 0x72, 0x00]);
+        assert_eq!(decoded, "wor");
+
+        let decoded = engine.decode(&[0x3d, 0xd8, 0x41, 0x00, 0x42]);
+        // Lone surrogate, and orphan byte
+        assert_eq!(decoded, "�A�");
+
+        let engine = Utf16Engine::new(Endianness::Big);
+
+        let decoded = engine.decode(&[0x77, 0x00, 0x6f, 0x00, 0x72, 0x00]);
+        // Wrong byte order
+        assert_eq!(decoded, "眀漀爀");
+
+        let engine = Utf16Engine::builder().bom(true).replacement('?').build();
+        assert_eq!(engine.get_name(), "UTF-16BE (with BOM, errors as '?')");
+
+        let decoded = engine.decode(&[0xfe, 0xff, 0x00, 0x41, 0xdc, 0x00,
+            0x42]);
+        assert_eq!(decoded, "A??");
+
+        assert!(engine.try_decode(&[0x00, 0x41, 0x00]).is_none());
+
+        let decoded = engine.try_decode(&[0xfe, 0xff, 0xff, 0xfd]);
+        assert_eq!(decoded.unwrap(), "�");
+
+        let decoded = engine.decode(&[0xff, 0xfe, 0x41, 0x00, 0x00, 0xdc]);
+        // Byte order mark of the other byte order
+        assert_eq!(decoded, "A?");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Utf16Engine::new(Endianness::Little);
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -2,23 +2,168 @@
 //!
 //! Encoding is performed with [`utf7_imap`](https://crates.io/crates/utf7-imap)
 //! which actually implements the modified UTF-7 of RFC 3501 (§5.1.3).
+//!
+//! Decoding is performed here, since that crate panics on invalid base64.
+//! Shifted sequences that are not valid base64, or that end with an incomplete
+//! UTF-16 unit, are decoded as U+FFFD � REPLACEMENT CHARACTER; an `'&'` that is
+//! never closed by a `'-'` is kept as is. Strict decoding fails on those
+//! sequences, and on invalid UTF-8, but accepts a genuine U+FFFD. Another
+//! character can be configured with [`Utf7Engine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+use utf7_imap::encode_utf7_imap;
+
+pub struct Utf7Engine
+{
+    /// The character to decode invalid sequences as.
+    pub replacement: char
+}
+
+/// Builder for a [`Utf7Engine`].
+pub struct Utf7EngineBuilder
+{
+    engine: Utf7Engine
+}
 
-use crate::engine::Engine;
+/// The modified base64 alphabet of RFC 3501, with `','` instead of `'/'`.
+const ALPHABET: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+,";
 
-use utf7_imap::{decode_utf7_imap,encode_utf7_imap};
+/// Decode the modified base64 between `'&'` and `'-'`, as UTF-16BE, with
+/// invalid sequences as `replacement`, or failing without one.
+fn decode_shifted(shifted: &str, replacement: Option<char>) -> Option<String>
+{
+    let mut units = Vec::new();
+    let mut bits = 0u32;
+    let mut count = 0;
 
-pub struct Utf7Engine {}
+    for c in shifted.chars()
+    {
+        let Some(value) = ALPHABET.find(c)
+        else
+        {
+            return replacement.map(String::from);
+        };
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 16
+        {
+            count -= 16;
+            units.push((bits >> count) as u16);
+            bits &= (1 << count) - 1;
+        }
+    }
+
+    let mut decoded = char::decode_utf16(units)
+        // Iterator<Result<char, DecodeUtf16Error>>
+        .map(|r| r.ok().or(replacement))
+        .collect::<Option<String>>()?;
+    if count >= 8
+    {
+        // Incomplete unit, not just padding bits
+        decoded.push(replacement?);
+    }
+    Some(decoded)
+}
+
+impl Utf7Engine
+{
+    /// The default engine, decoding invalid sequences as U+FFFD.
+    pub const fn new() -> Self
+    {
+        Utf7Engine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Utf7EngineBuilder
+    {
+        Utf7EngineBuilder { engine: Utf7Engine::new() }
+    }
+
+    /// Decode, with invalid UTF-8 and invalid shifted sequences as
+    /// `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let mut text = String::new();
+        for chunk in bytes.utf8_chunks()
+        {
+            text.push_str(chunk.valid());
+            if !chunk.invalid().is_empty()
+            {
+                text.push(replacement?);
+            }
+        }
+        let mut decoded = String::new();
+
+        let mut rest = &*text;
+        while let Some(start) = rest.find('&')
+        {
+            decoded.push_str(&rest[..start]);
+            let Some(length) = rest[start..].find('-')
+            else
+            {
+                rest = &rest[start..];
+                break;
+            };
+            let shifted = &rest[start + 1..start + length];
+            if shifted.is_empty()
+            {
+                decoded.push('&');
+            }
+            else
+            {
+                decoded.push_str(&decode_shifted(shifted, replacement)?);
+            }
+            rest = &rest[start + length + 1..];
+        }
+        decoded.push_str(rest);
+
+        Some(decoded)
+    }
+}
+
+impl Default for Utf7Engine
+{
+    fn default() -> Self { Utf7Engine::new() }
+}
+
+impl Utf7EngineBuilder
+{
+    /// Set the character to decode invalid sequences as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Utf7Engine { self.engine }
+}
 
 impl Engine for Utf7Engine
 {
-    fn get_name(&self) -> String { "UTF-7".to_string() }
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("UTF-7", &options)
+    }
     fn encode(&self, string: &str) -> Option<Vec<u8>>
     {
         Some(Vec::from(encode_utf7_imap(string.to_owned()).as_bytes()))
     }
     fn decode(&self, bytes: &[u8]) -> String
     {
-        decode_utf7_imap(String::from_utf8_lossy(bytes).into_owned())
+        // Cannot fail with a replacement
+        Utf7Engine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        Utf7Engine::decode_with(bytes, None)
     }
 }
 
@@ -26,9 +171,11 @@ impl Engine for Utf7Engine
 mod tests {
     use super::*;
 
+    use crate::engine::conformance;
+
     #[test]
     fn encode() {
-        let engine = Utf7Engine{};
+        let engine = Utf7Engine::new();
 
         let encoded = engine.encode("Hello").unwrap();
         assert_eq!(encoded, b"Hello");
@@ -62,7 +209,7 @@ mod tests {
     #[test]
     fn decode()
     {
-        let engine = Utf7Engine{};
+        let engine = Utf7Engine::new();
 
         let decoded = engine.decode(b"world&ACE-");
         assert_eq!(decoded, "world!");
@@ -80,5 +227,33 @@ mod tests {
         // => 0010 0000 1010 0100
         // => U+20A4
         assert_eq!(decoded, "₤");
+
+        let decoded = engine.decode(b"&&- &A=- &AOkA- &AOk");
+        // Invalid base64, incomplete unit, and unclosed shift
+        assert_eq!(decoded, "� � é� &AOk");
+
+        assert!(engine.try_decode(b"&AOkA-").is_none());
+
+        let decoded = engine.try_decode(b"&,,0-");
+        // ,,0 => 111111 111111 1101(00)
+        // => U+FFFD
+        assert_eq!(decoded.unwrap(), "�");
+
+        let engine = Utf7Engine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "UTF-7 (errors as '?')");
+
+        let decoded = engine.decode(b"&A=- \xe9 &AOk-");
+        assert_eq!(decoded, "? ? é");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Utf7Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
     }
 }
@@ -1,18 +1,246 @@
-use crate::engine::Engine;
+//! Deencoding engine for UTF-8
+//!
+//! The default engine writes no byte order mark, and decodes each invalid
+//! sequence as U+FFFD � REPLACEMENT CHARACTER, like
+//! [`String::from_utf8_lossy()`] does. The engine can be configured with
+//! [`Utf8Engine::builder()`]:
+//! * to write a byte order mark, and sniff one when reading;
+//! * to be lenient, like many older decoders, and accept overlong forms, such
+//!   as `C0 80` for U+0000 in Java's modified UTF-8, and surrogates, paired
+//!   as in CESU-8; other invalid bytes are then replaced one by one;
+//! * to decode invalid sequences as another character, such as `'?'`.
+//!
+//! With the `bom` option, decoding sniffs any byte order mark and switches
+//! encodings accordingly, as browsers do, like a
+//! [`BomEngine`](crate::bomengine::BomEngine) wrapping the engine; the rest is
+//! decoded with the configuration of the engine when the mark is UTF-8.
 
-pub struct Utf8Engine {}
+use crate::bomengine::{decode_after, sniff, Bom};
+use crate::engine::{name_with_options, Engine};
+
+/// The byte order mark, U+FEFF in UTF-8.
+const BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+
+pub struct Utf8Engine
+{
+    /// Whether to write a byte order mark, and sniff one when reading.
+    pub bom: bool,
+    /// Whether to reject overlong forms and surrogates when decoding.
+    pub strict: bool,
+    /// The character to decode invalid sequences as.
+    pub replacement: char
+}
+
+/// Builder for a [`Utf8Engine`].
+pub struct Utf8EngineBuilder
+{
+    engine: Utf8Engine
+}
+
+impl Utf8Engine
+{
+    /// The default engine: no byte order mark, strict, and U+FFFD.
+    pub const fn new() -> Self
+    {
+        Utf8Engine {
+            bom: false,
+            strict: true,
+            replacement: char::REPLACEMENT_CHARACTER
+        }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> Utf8EngineBuilder
+    {
+        Utf8EngineBuilder { engine: Utf8Engine::new() }
+    }
+
+    /// Decode, rejecting overlong forms and surrogates, and decoding invalid
+    /// sequences as `replacement`, or failing without one.
+    fn decode_strict(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let mut decoded = String::new();
+
+        for chunk in bytes.utf8_chunks()
+        {
+            decoded.push_str(chunk.valid());
+            if !chunk.invalid().is_empty()
+            {
+                decoded.push(replacement?);
+            }
+        }
+
+        Some(decoded)
+    }
+
+    /// Decode, accepting overlong forms and surrogates, and decoding invalid
+    /// sequences as `replacement`, or failing without one.
+    fn decode_lenient(bytes: &[u8], replacement: Option<char>)
+        -> Option<String>
+    {
+        let mut decoded = String::new();
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            match read_sequence(&bytes[i..])
+            {
+                Some((high @ 0xd800..=0xdbff, length)) =>
+                {
+                    match read_sequence(&bytes[i + length..])
+                    {
+                        Some((low @ 0xdc00..=0xdfff, next)) =>
+                        {
+                            let scalar = 0x10000 + ((high - 0xd800) << 10)
+                                + (low - 0xdc00);
+                            decoded.push(char::from_u32(scalar).unwrap());
+                            i += length + next;
+                        },
+                        _ =>
+                        {
+                            // Lone high surrogate
+                            decoded.push(replacement?);
+                            i += length;
+                        }
+                    }
+                },
+                Some((scalar, length)) =>
+                {
+                    // Lone low surrogates and scalars above U+10FFFF are
+                    // still invalid
+                    decoded.push(char::from_u32(scalar).or(replacement)?);
+                    i += length;
+                },
+                None =>
+                {
+                    decoded.push(replacement?);
+                    i += 1;
+                }
+            }
+        }
+
+        Some(decoded)
+    }
+
+    /// Decode, with the configuration of the engine.
+    fn decode_with(&self, bytes: &[u8], replacement: Option<char>)
+        -> Option<String>
+    {
+        let sniffed = if self.bom { sniff(bytes) } else { None };
+        let bytes = match sniffed
+        {
+            Some((Bom::Utf8, rest)) => rest,
+            Some((bom, rest)) => return decode_after(bom, rest, replacement),
+            None => bytes
+        };
+
+        if self.strict
+        {
+            Utf8Engine::decode_strict(bytes, replacement)
+        }
+        else
+        {
+            Utf8Engine::decode_lenient(bytes, replacement)
+        }
+    }
+}
+
+impl Default for Utf8Engine
+{
+    fn default() -> Self { Utf8Engine::new() }
+}
+
+impl Utf8EngineBuilder
+{
+    /// Set whether to write a byte order mark, and sniff one when reading.
+    pub const fn bom(mut self, bom: bool) -> Self
+    {
+        self.engine.bom = bom;
+        self
+    }
+
+    /// Set whether to reject overlong forms and surrogates when decoding.
+    pub const fn strict(mut self, strict: bool) -> Self
+    {
+        self.engine.strict = strict;
+        self
+    }
+
+    /// Set the character to decode invalid sequences as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> Utf8Engine { self.engine }
+}
+
+/// Read a sequence of a lead byte and its continuation bytes, without checking
+/// the value, giving the value and the length of the sequence.
+fn read_sequence(bytes: &[u8]) -> Option<(u32, usize)>
+{
+    let first = *bytes.first()?;
+    let (mut value, length) = match first
+    {
+        b @ 0x00..=0x7f => (b as u32, 1),
+        b @ 0xc0..=0xdf => ((b & 0x1f) as u32, 2),
+        b @ 0xe0..=0xef => ((b & 0x0f) as u32, 3),
+        b @ 0xf0..=0xf7 => ((b & 0x07) as u32, 4),
+        _ => return None
+    };
+
+    for k in 1..length
+    {
+        let b = *bytes.get(k)?;
+        if b & 0xc0 != 0x80
+        {
+            return None;
+        }
+        value = value << 6 | (b & 0x3f) as u32;
+    }
+
+    Some((value, length))
+}
 
 impl Engine for Utf8Engine
 {
-    fn get_name(&self) -> String { "UTF-8".to_string() }
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.bom
+        {
+            options.push("with BOM".to_string());
+        }
+        if !self.strict
+        {
+            options.push("lenient".to_string());
+        }
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("UTF-8", &options)
+    }
     fn encode(&self, string: &str) -> Option<Vec<u8>>
     {
-        Some(Vec::from(string.as_bytes()))
+        let mut encoded = Vec::new();
+        if self.bom
+        {
+            encoded.extend(BOM);
+        }
+        encoded.extend(string.as_bytes());
+        Some(encoded)
     }
     fn decode(&self, bytes: &[u8]) -> String
     {
-        String::from_utf8_lossy(bytes) // Cow<'_, str>
-            .into_owned() // &str
+        // Cannot fail with a replacement
+        self.decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        self.decode_with(bytes, None)
     }
 }
 
@@ -20,9 +248,11 @@ impl Engine for Utf8Engine
 mod tests {
     use super::*;
 
+    use crate::engine::conformance;
+
     #[test]
     fn encode() {
-        let engine = Utf8Engine{};
+        let engine = Utf8Engine::new();
 
         let encoded = engine.encode("Hello").unwrap();
         assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
@@ -52,13 +282,19 @@ mod tests {
         // => Leading4(000) Continuation(011111) Continuation(011000) Continuation(000000)
         // => 11110_000 10_011111 10_011000 10_00000
         // => f0 9f 98 80
-        assert_eq!(encoded, &[0xf0, 0x9f, 0x98, 0x80])
+        assert_eq!(encoded, &[0xf0, 0x9f, 0x98, 0x80]);
+
+        let engine = Utf8Engine::builder().bom(true).build();
+        assert_eq!(engine.get_name(), "UTF-8 (with BOM)");
+
+        let encoded = engine.encode("é").unwrap();
+        assert_eq!(encoded, &[0xef, 0xbb, 0xbf, 0xc3, 0xa9]);
     }
 
     #[test]
     fn decode()
     {
-        let engine = Utf8Engine{};
+        let engine = Utf8Engine::new();
 
         let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
         assert_eq!(decoded, "world!");
@@ -80,5 +316,66 @@ mod tests {
         // => 0010 0000 1010 0100
         // => U+20A4
         assert_eq!(decoded, "₤");
+
+        let decoded = engine.decode(&[0xc0, 0x80, 0xed, 0xa0, 0xbd, 0xed, 0xb8,
+            0x80]);
+        // Overlong form, and surrogate pair
+        assert_eq!(decoded, "��������");
+
+        let engine = Utf8Engine::builder()
+            .bom(true)
+            .strict(false)
+            .replacement('?')
+            .build();
+        assert_eq!(engine.get_name(),
+            "UTF-8 (with BOM, lenient, errors as '?')");
+
+        let decoded = engine.decode(&[0xef, 0xbb, 0xbf, 0xc0, 0x80, 0xed, 0xa0,
+            0xbd, 0xed, 0xb8, 0x80]);
+        assert_eq!(decoded, "\0😀");
+
+        let decoded = engine.decode(&[0xff, 0xfe, 0xe9, 0x00, 0x00, 0xdc]);
+        // Byte order mark of UTF-16LE
+        assert_eq!(decoded, "é?");
+
+        let decoded = engine.decode(&[0xed, 0xa0, 0xbd, 0x41, 0xe9, 0xf8]);
+        // Lone surrogate, and invalid bytes
+        assert_eq!(decoded, "?A??");
+
+        let decoded = engine.decode(&[0x41, 0xed, 0xa0, 0xbd]);
+        // Lone surrogate at the end
+        assert_eq!(decoded, "A?");
+
+        assert!(engine.try_decode(&[0xed, 0xa0, 0xbd, 0x41]).is_none());
+
+        let decoded = engine.try_decode(&[0xc0, 0x80, 0xef, 0xbf, 0xbd]);
+        assert_eq!(decoded.unwrap(), "\0�");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = Utf8Engine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+
+        let engine = Utf8Engine::builder().strict(false).build();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+
+        let engine = Utf8Engine::builder().bom(true).replacement('?').build();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
     }
 }
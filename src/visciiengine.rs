@@ -0,0 +1,159 @@
+//! Deencoding engine for VISCII
+//!
+//! VISCII (RFC 1456) fits all 134 precomposed Vietnamese letters in a single
+//! byte charset, by using the whole upper half, and taking over six C0
+//! control codes for the remaining capital letters.
+//!
+//! Encoding and decoding are performed with a table, since no crate supports
+//! VISCII. Encoding fails on characters outside of the table. Every byte is
+//! defined, so there are no undefined bytes to configure a replacement for.
+
+use crate::engine::Engine;
+
+pub struct VisciiEngine {}
+
+impl VisciiEngine
+{
+    /// The engine.
+    pub const fn new() -> Self { VisciiEngine {} }
+}
+
+impl Default for VisciiEngine
+{
+    fn default() -> Self { VisciiEngine::new() }
+}
+
+/// The C0 control codes replaced by capital letters.
+const LOWER: [(u8, char); 6] = [
+    (0x02, 'Ẳ'), (0x05, 'Ẵ'), (0x06, 'Ẫ'),
+    (0x14, 'Ỷ'), (0x19, 'Ỹ'), (0x1e, 'Ỵ'),
+];
+
+/// The upper half of VISCII, from `0x80` to `0xFF`.
+const UPPER: [char; 128] = [
+    // 80
+    'Ạ', 'Ắ', 'Ằ', 'Ặ', 'Ấ', 'Ầ', 'Ẩ', 'Ậ',
+    'Ẽ', 'Ẹ', 'Ế', 'Ề', 'Ể', 'Ễ', 'Ệ', 'Ố',
+    // 90
+    'Ồ', 'Ổ', 'Ỗ', 'Ộ', 'Ợ', 'Ớ', 'Ờ', 'Ở',
+    'Ị', 'Ỏ', 'Ọ', 'Ỉ', 'Ủ', 'Ũ', 'Ụ', 'Ỳ',
+    // A0
+    'Õ', 'ắ', 'ằ', 'ặ', 'ấ', 'ầ', 'ẩ', 'ậ',
+    'ẽ', 'ẹ', 'ế', 'ề', 'ể', 'ễ', 'ệ', 'ố',
+    // B0
+    'ồ', 'ổ', 'ỗ', 'Ỡ', 'Ơ', 'ộ', 'ờ', 'ở',
+    'ị', 'Ự', 'Ứ', 'Ừ', 'Ử', 'ơ', 'ớ', 'Ư',
+    // C0
+    'À', 'Á', 'Â', 'Ã', 'Ả', 'Ă', 'ẳ', 'ẵ',
+    'È', 'É', 'Ê', 'Ẻ', 'Ì', 'Í', 'Ĩ', 'ỳ',
+    // D0
+    'Đ', 'ứ', 'Ò', 'Ó', 'Ô', 'ạ', 'ỷ', 'ừ',
+    'ử', 'Ù', 'Ú', 'ỹ', 'ỵ', 'Ý', 'ỡ', 'ư',
+    // E0
+    'à', 'á', 'â', 'ã', 'ả', 'ă', 'ữ', 'ẫ',
+    'è', 'é', 'ê', 'ẻ', 'ì', 'í', 'ĩ', 'ỉ',
+    // F0
+    'đ', 'ự', 'ò', 'ó', 'ô', 'õ', 'ỏ', 'ọ',
+    'ụ', 'ù', 'ú', 'ũ', 'ủ', 'ý', 'ợ', 'Ữ',
+];
+
+impl Engine for VisciiEngine
+{
+    fn get_name(&self) -> String { "VISCII".to_string() }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            let byte = if let Some((b, _)) = LOWER.iter().find(|(_, l)| *l == c)
+            {
+                *b
+            }
+            else if let Some(i) = UPPER.iter().position(|&u| u == c)
+            {
+                0x80 + i as u8
+            }
+            else if c.is_ascii() && LOWER.iter().all(|(b, _)| *b != c as u8)
+            {
+                c as u8
+            }
+            else
+            {
+                return None;
+            };
+            encoded.push(byte);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        bytes.iter()
+            .map(|&b|
+                if b >= 0x80
+                {
+                    UPPER[(b - 0x80) as usize]
+                }
+                else
+                {
+                    LOWER.iter()
+                        .find(|(l, _)| *l == b)
+                        .map_or(b as char, |(_, c)| *c)
+                })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = VisciiEngine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("Nguyễn").unwrap();
+        assert_eq!(encoded, &[0x4e, 0x67, 0x75, 0x79, 0xad, 0x6e]);
+
+        let encoded = engine.encode("Ỹ").unwrap();
+        assert_eq!(encoded, &[0x19]);
+
+        assert!(engine.encode("\u{19}").is_none());
+        assert!(engine.encode("ç").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = VisciiEngine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(&[0xd0, 0xe0, 0x20, 0x4e, 0xc7, 0x6e,
+            0x67]);
+        assert_eq!(decoded, "Đà Nẵng");
+
+        let decoded = engine.decode(&[0x02, 0x1e]);
+        assert_eq!(decoded, "ẲỴ");
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = VisciiEngine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+    }
+}
@@ -0,0 +1,297 @@
+//! Deencoding engine for VNI
+//!
+//! VNI is the encoding of the VNI fonts, which were the most common way to
+//! write Vietnamese in southern Vietnam and abroad before Unicode. Most letters
+//! are written as an ASCII base letter followed by a byte for the diacritics,
+//! drawn by the font as a zero-width glyph: `'ễ'` is `'e'` followed by `E3`,
+//! which is why "Nguyễn" shows up as "Nguyeãn" in Windows-1252. The diacritic
+//! bytes come in a lowercase and a capital variant, drawn at different
+//! heights; `'đ'`, `'ơ'`, `'ư'` and the `'i'` with a tone mark have a byte of
+//! their own.
+//!
+//! Encoding decomposes each character to find its base letter and diacritics,
+//! and fails on characters that VNI cannot write. Decoding reads the longest
+//! sequence that makes a character; diacritic bytes without a base letter, or
+//! after the wrong base letter, and other unassigned bytes are decoded as
+//! U+FFFD � REPLACEMENT CHARACTER, or as another character configured with
+//! [`VniEngine::builder()`].
+
+use crate::engine::{name_with_options, Engine};
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use unicode_normalization::char::decompose_canonical;
+
+pub struct VniEngine
+{
+    /// The character to decode invalid sequences as.
+    pub replacement: char
+}
+
+/// Builder for a [`VniEngine`].
+pub struct VniEngineBuilder
+{
+    engine: VniEngine
+}
+
+impl VniEngine
+{
+    /// The default engine, decoding invalid sequences as U+FFFD.
+    pub const fn new() -> Self
+    {
+        VniEngine { replacement: char::REPLACEMENT_CHARACTER }
+    }
+
+    /// Configure an engine, starting from the default one.
+    pub const fn builder() -> VniEngineBuilder
+    {
+        VniEngineBuilder { engine: VniEngine::new() }
+    }
+
+    /// Decode, with invalid sequences as `replacement`, or failing without one.
+    fn decode_with(bytes: &[u8], replacement: Option<char>) -> Option<String>
+    {
+        let sequences = sequences();
+        let mut decoded = String::new();
+
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            if let Some(&c) = bytes.get(i..i + 2)
+                .and_then(|pair| sequences.get(pair))
+            {
+                decoded.push(c);
+                i += 2;
+                continue;
+            }
+            match sequences.get(&bytes[i..i + 1])
+            {
+                Some(&c) => decoded.push(c),
+                None if bytes[i].is_ascii() => decoded.push(bytes[i] as char),
+                None => decoded.push(replacement?)
+            }
+            i += 1;
+        }
+
+        Some(decoded)
+    }
+}
+
+impl Default for VniEngine
+{
+    fn default() -> Self { VniEngine::new() }
+}
+
+impl VniEngineBuilder
+{
+    /// Set the character to decode invalid sequences as.
+    pub const fn replacement(mut self, replacement: char) -> Self
+    {
+        self.engine.replacement = replacement;
+        self
+    }
+
+    /// Build the engine.
+    pub const fn build(self) -> VniEngine { self.engine }
+}
+
+/// The tone marks, in the order of the columns of the tables below. The
+/// first column is for letters without a tone mark.
+const TONE_MARKS: [char; 5] = ['\u{300}', '\u{301}', '\u{309}', '\u{303}',
+    '\u{323}'];
+
+/// Diacritic bytes for a tone mark alone, for lowercase and capital letters.
+const TONE: [[u8; 6]; 2] = [
+    [0x00, 0xf8, 0xf9, 0xfb, 0xf5, 0xef],
+    [0x00, 0xd8, 0xd9, 0xdb, 0xd5, 0xcf],
+];
+
+/// Diacritic bytes for a circumflex and a tone mark.
+const CIRCUMFLEX: [[u8; 6]; 2] = [
+    [0xe2, 0xe0, 0xe1, 0xe5, 0xe3, 0xe4],
+    [0xc2, 0xc0, 0xc1, 0xc5, 0xc3, 0xc4],
+];
+
+/// Diacritic bytes for a breve and a tone mark.
+const BREVE: [[u8; 6]; 2] = [
+    [0xea, 0xe8, 0xe9, 0xfa, 0xfc, 0xeb],
+    [0xca, 0xc8, 0xc9, 0xda, 0xdc, 0xcb],
+];
+
+/// Bytes for `'i'` with a tone mark.
+const I: [[u8; 6]; 2] = [
+    [b'i', 0xec, 0xed, 0xe6, 0xf3, 0xf2],
+    [b'I', 0xcc, 0xcd, 0xc6, 0xd3, 0xd2],
+];
+
+/// Letters with a byte of their own.
+const LETTERS: [(char, u8); 6] = [
+    ('đ', 0xf1), ('Đ', 0xd1), ('ơ', 0xf4),
+    ('Ơ', 0xd4), ('ư', 0xf6), ('Ư', 0xd6),
+];
+
+/// Encode a single character.
+fn encode_char(c: char) -> Option<Vec<u8>>
+{
+    if c.is_ascii()
+    {
+        return Some(vec![c as u8]);
+    }
+    if let Some((_, b)) = LETTERS.iter().find(|(l, _)| *l == c)
+    {
+        return Some(vec![*b]);
+    }
+
+    let mut decomposition = Vec::new();
+    decompose_canonical(c, |d| decomposition.push(d));
+    let base = decomposition[0];
+    let case = base.is_uppercase() as usize;
+    let mut tone = 0;
+    let mut modifier = None;
+    for &mark in &decomposition[1..]
+    {
+        if let Some(i) = TONE_MARKS.iter().position(|&t| t == mark)
+        {
+            tone = i + 1;
+        }
+        else if modifier.is_none()
+        {
+            modifier = Some(mark);
+        }
+        else
+        {
+            return None;
+        }
+    }
+
+    let byte = |table: &[[u8; 6]; 2]| table[case][tone];
+    let bytes = match (base.to_ascii_lowercase(), modifier)
+    {
+        ('i', None) => vec![byte(&I)],
+        ('a' | 'e' | 'o' | 'u' | 'y', None) if tone > 0 =>
+            vec![base as u8, byte(&TONE)],
+        ('a' | 'e' | 'o', Some('\u{302}')) =>
+            vec![base as u8, byte(&CIRCUMFLEX)],
+        ('a', Some('\u{306}')) => vec![base as u8, byte(&BREVE)],
+        ('o' | 'u', Some('\u{31b}')) =>
+        {
+            let horn = if base == 'o' || base == 'O' { 0xf4 } else { 0xf6 };
+            let mut bytes = vec![horn - 0x20 * case as u8];
+            if tone > 0
+            {
+                bytes.push(byte(&TONE));
+            }
+            bytes
+        },
+        _ => return None
+    };
+
+    Some(bytes)
+}
+
+/// The characters written with more than a single ASCII byte, by sequence.
+fn sequences() -> &'static HashMap<Vec<u8>, char>
+{
+    static SEQUENCES: OnceLock<HashMap<Vec<u8>, char>> = OnceLock::new();
+    SEQUENCES.get_or_init(||
+        ('\u{c0}'..='\u{1b0}').chain('\u{1ea0}'..='\u{1ef9}')
+            .filter_map(|c| encode_char(c).map(|bytes| (bytes, c)))
+            .collect())
+}
+
+impl Engine for VniEngine
+{
+    fn get_name(&self) -> String
+    {
+        let mut options = Vec::new();
+        if self.replacement != char::REPLACEMENT_CHARACTER
+        {
+            options.push(format!("errors as {:?}", self.replacement));
+        }
+        name_with_options("VNI", &options)
+    }
+
+    fn encode(&self, string: &str) -> Option<Vec<u8>>
+    {
+        let mut encoded = Vec::new();
+
+        for c in string.chars()
+        {
+            encoded.extend(encode_char(c)?);
+        }
+
+        Some(encoded)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String
+    {
+        // Cannot fail with a replacement
+        VniEngine::decode_with(bytes, Some(self.replacement)).unwrap()
+    }
+
+    fn try_decode(&self, bytes: &[u8]) -> Option<String>
+    {
+        VniEngine::decode_with(bytes, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::conformance;
+
+    #[test]
+    fn encode() {
+        let engine = VniEngine::new();
+
+        let encoded = engine.encode("Hello").unwrap();
+        assert_eq!(encoded, &[0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let encoded = engine.encode("Nguyễn").unwrap();
+        assert_eq!(encoded, b"Nguye\xe3n");
+
+        let encoded = engine.encode("Đặng Ờ í").unwrap();
+        assert_eq!(encoded, b"\xd1a\xebng \xd4\xd8 \xed");
+
+        assert!(engine.encode("ç").is_none());
+        assert!(engine.encode("ü").is_none());
+    }
+
+    #[test]
+    fn decode()
+    {
+        let engine = VniEngine::new();
+
+        let decoded = engine.decode(&[0x77, 0x6f, 0x72, 0x6c, 0x64, 0x21]);
+        assert_eq!(decoded, "world!");
+
+        let decoded = engine.decode(b"Vie\xe4t Nam");
+        assert_eq!(decoded, "Việt Nam");
+
+        let decoded = engine.decode(b"\xf6\xf9 \xe3 b\xe3");
+        // Diacritics without a base letter
+        assert_eq!(decoded, "ứ � b�");
+
+        let engine = VniEngine::builder().replacement('?').build();
+        assert_eq!(engine.get_name(), "VNI (errors as '?')");
+
+        let decoded = engine.decode(b"\xe3 b\xe3");
+        assert_eq!(decoded, "? b?");
+
+        assert!(engine.try_decode(b"b\xe3").is_none());
+    }
+
+    #[test]
+    fn conformance()
+    {
+        let engine = VniEngine::new();
+
+        conformance::assert_deterministic_name(&engine);
+        conformance::assert_decode_never_panics(&engine);
+        conformance::assert_try_decode_agrees(&engine);
+        conformance::assert_round_trips(&engine, conformance::SAMPLES);
+        conformance::assert_ascii_transparent(&engine);
+    }
+}